@@ -0,0 +1,122 @@
+use std::ops::{Add, Sub};
+
+use crate::vectors::{Vec2, VecN};
+
+// axis-aligned bounding box, min and max are both inclusive
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Aabb<T, const N: usize> {
+    pub min: VecN<T, N>,
+    pub max: VecN<T, N>,
+}
+
+impl<T, const N: usize> Aabb<T, N> where T: Copy + PartialOrd {
+    // the corners may be given in any order
+    pub fn new(a: VecN<T, N>, b: VecN<T, N>) -> Self {
+        Aabb {
+            min: a.component_min(&b),
+            max: a.component_max(&b),
+        }
+    }
+
+    pub fn from_point(point: VecN<T, N>) -> Self {
+        Aabb { min: point, max: point }
+    }
+
+    // None if there are no points
+    pub fn from_points<I>(points: I) -> Option<Self> where I: IntoIterator<Item=VecN<T, N>> {
+        let mut points = points.into_iter();
+        let first = Aabb::from_point(points.next()?);
+        Some(points.fold(first, |aabb, p| aabb.union(&Aabb::from_point(p))))
+    }
+
+    pub fn include(&mut self, point: VecN<T, N>) {
+        *self = self.union(&Aabb::from_point(point));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Aabb {
+            min: self.min.component_min(&other.min),
+            max: self.max.component_max(&other.max),
+        }
+    }
+
+    // None if the boxes do not overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.component_max(&other.min);
+        let max = self.max.component_min(&other.max);
+        if (0..N).all(|i| min[i] <= max[i]) {
+            Some(Aabb { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, point: &VecN<T, N>) -> bool {
+        (0..N).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    // grows the box by margin in every direction
+    pub fn expand(&self, margin: T) -> Self where T: Add<Output=T> + Sub<Output=T> {
+        Aabb {
+            min: self.min.map(|c| c - margin),
+            max: self.max.map(|c| c + margin),
+        }
+    }
+}
+
+impl<const N: usize> Aabb<i32, N> {
+    // number of integer points per axis
+    pub fn size(&self) -> VecN<i32, N> {
+        self.max - self.min + VecN::splat(1)
+    }
+
+    // number of contained integer points
+    pub fn volume(&self) -> u64 {
+        self.size().iter().map(|s| *s as u64).product()
+    }
+
+    // all contained integer points, the first component changes fastest
+    pub fn points(&self) -> Points<N> {
+        Points { aabb: *self, next: Some(self.min) }
+    }
+}
+
+impl Aabb<i32, 2> {
+    // one line per y, one character per x
+    pub fn render<F>(&self, cell: F) -> String where F: Fn(Vec2<i32>) -> char {
+        let mut result = String::new();
+        for y in self.min.y..=self.max.y {
+            result.extend((self.min.x..=self.max.x).map(|x| cell(Vec2::new(x, y))));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+pub struct Points<const N: usize> {
+    aabb: Aabb<i32, N>,
+    next: Option<VecN<i32, N>>,
+}
+
+impl<const N: usize> Iterator for Points<N> {
+    type Item = VecN<i32, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mut next = current;
+        self.next = None;
+        for i in 0..N {
+            if next[i] < self.aabb.max[i] {
+                next[i] += 1;
+                self.next = Some(next);
+                break;
+            }
+            next[i] = self.aabb.min[i];
+        }
+        Some(current)
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+// Assigns every key exactly one of its candidate values, no value being used twice.
+#[derive(Clone, Debug)]
+pub struct AssignmentSolver<K, V> {
+    candidates: BTreeMap<K, BTreeSet<V>>,
+    max_alternatives: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Solution<K, V> {
+    Unique(BTreeMap<K, V>),
+    // at least two (and at most max_alternatives) complete assignments
+    Ambiguous(Vec<BTreeMap<K, V>>),
+    Unsatisfiable,
+}
+
+impl<K, V> Solution<K, V> {
+    pub fn unique(self) -> Option<BTreeMap<K, V>> {
+        match self {
+            Solution::Unique(assignment) => Some(assignment),
+            _ => None,
+        }
+    }
+}
+
+impl<K, V> Default for AssignmentSolver<K, V> where K: Ord + Clone, V: Ord + Clone {
+    fn default() -> Self {
+        AssignmentSolver::new()
+    }
+}
+
+impl<K, V> AssignmentSolver<K, V> where K: Ord + Clone, V: Ord + Clone {
+    pub fn new() -> Self {
+        AssignmentSolver {
+            candidates: BTreeMap::new(),
+            max_alternatives: 10,
+        }
+    }
+
+    pub fn with_max_alternatives(mut self, max_alternatives: usize) -> Self {
+        self.max_alternatives = max_alternatives.max(2);
+        self
+    }
+
+    // adding the same key twice intersects the candidate sets
+    pub fn add<I>(&mut self, key: K, candidates: I) where I: IntoIterator<Item=V> {
+        let new: BTreeSet<V> = candidates.into_iter().collect();
+        let entry = self.candidates.entry(key).or_insert_with(|| new.clone());
+        *entry = entry.intersection(&new).cloned().collect();
+    }
+
+    pub fn candidates(&self) -> &BTreeMap<K, BTreeSet<V>> {
+        &self.candidates
+    }
+
+    // assignments that follow from naked singles alone, without guessing
+    pub fn propagate(&self) -> Option<BTreeMap<K, V>> {
+        let mut candidates = self.candidates.clone();
+        let mut assigned = BTreeMap::new();
+        if AssignmentSolver::propagate_singles(&mut candidates, &mut assigned) {
+            Some(assigned)
+        } else {
+            None
+        }
+    }
+
+    pub fn solve(&self) -> Solution<K, V> {
+        let mut solutions = Vec::new();
+        AssignmentSolver::search(self.candidates.clone(), BTreeMap::new(), &mut solutions, self.max_alternatives);
+        match solutions.len() {
+            0 => Solution::Unsatisfiable,
+            1 => Solution::Unique(solutions.pop().unwrap()),
+            _ => Solution::Ambiguous(solutions),
+        }
+    }
+
+    fn search(mut candidates: BTreeMap<K, BTreeSet<V>>, mut assigned: BTreeMap<K, V>, solutions: &mut Vec<BTreeMap<K, V>>, limit: usize) {
+        if !AssignmentSolver::propagate_singles(&mut candidates, &mut assigned) {
+            return;
+        }
+        // branch on the key with the fewest options left
+        let branch = candidates.iter()
+            .min_by_key(|(_, values)| values.len())
+            .map(|(key, values)| (key.clone(), values.clone()));
+        match branch {
+            None => solutions.push(assigned),
+            Some((key, values)) => {
+                for value in values {
+                    if solutions.len() >= limit {
+                        return;
+                    }
+                    let mut next = candidates.clone();
+                    next.insert(key.clone(), std::iter::once(value).collect());
+                    AssignmentSolver::search(next, assigned.clone(), solutions, limit);
+                }
+            }
+        }
+    }
+
+    // Moves every key with exactly one candidate into assigned and removes that value from all
+    // other keys until nothing changes. Returns false if some key runs out of candidates.
+    fn propagate_singles(candidates: &mut BTreeMap<K, BTreeSet<V>>, assigned: &mut BTreeMap<K, V>) -> bool {
+        loop {
+            if candidates.values().any(|values| values.is_empty()) {
+                return false;
+            }
+            let single = candidates.iter()
+                .find(|(_, values)| values.len() == 1)
+                .map(|(key, values)| (key.clone(), values.iter().next().unwrap().clone()));
+            match single {
+                None => return true,
+                Some((key, value)) => {
+                    candidates.remove(&key);
+                    for values in candidates.values_mut() {
+                        values.remove(&value);
+                    }
+                    assigned.insert(key, value);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+
+use crate::aabb::Aabb;
+use crate::hashing::FastBuildHasher;
+use crate::vectors::VecN;
+
+pub trait Cell: Clone + Eq + Hash {
+    fn offset(&self, by: &Self) -> Self;
+}
+
+impl<const N: usize> Cell for VecN<i32, N> {
+    fn offset(&self, by: &Self) -> Self {
+        *self + *by
+    }
+}
+
+// set of live cells on an unbounded grid, the neighborhood is given as offsets
+#[derive(Clone, Debug)]
+pub struct SparseAutomaton<C, S = FastBuildHasher> where C: Cell, S: BuildHasher + Default {
+    neighborhood: Vec<C>,
+    cells: HashSet<C, S>,
+}
+
+impl<C, S> SparseAutomaton<C, S> where C: Cell, S: BuildHasher + Default {
+    pub fn new(neighborhood: Vec<C>) -> Self {
+        SparseAutomaton {
+            neighborhood,
+            cells: HashSet::default(),
+        }
+    }
+
+    pub fn cells(&self) -> &HashSet<C, S> {
+        &self.cells
+    }
+
+    pub fn is_set(&self, cell: &C) -> bool {
+        self.cells.contains(cell)
+    }
+
+    pub fn insert(&mut self, cell: C) {
+        self.cells.insert(cell);
+    }
+
+    pub fn remove(&mut self, cell: &C) {
+        self.cells.remove(cell);
+    }
+
+    pub fn toggle(&mut self, cell: C) {
+        if !self.cells.remove(&cell) {
+            self.cells.insert(cell);
+        }
+    }
+
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn neighbors<'a>(&'a self, cell: &'a C) -> impl Iterator<Item=C> + 'a {
+        self.neighborhood.iter().map(move |offset| cell.offset(offset))
+    }
+
+    pub fn count_neighbors(&self, cell: &C) -> usize {
+        self.neighbors(cell).filter(|n| self.is_set(n)).count()
+    }
+
+    // every live cell adds one to each of its neighbors; cells that end up with no entry have no live neighbors
+    pub fn neighbor_counts(&self) -> HashMap<C, usize, S> {
+        let mut counts: HashMap<C, usize, S> = HashMap::with_capacity_and_hasher(self.cells.len() * self.neighborhood.len(), S::default());
+        for cell in &self.cells {
+            for neighbor in self.neighbors(cell) {
+                *counts.entry(neighbor).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    // rule gets whether a cell is alive and the number of its live neighbors and decides whether it lives on;
+    // cells without any live neighbors are only passed to rule if they are alive themselves
+    pub fn step<F>(&mut self, rule: F) where F: Fn(bool, usize) -> bool {
+        let counts = self.neighbor_counts();
+        let mut next: HashSet<C, S> = self.cells.iter()
+            .filter(|cell| !counts.contains_key(cell) && rule(true, 0))
+            .cloned()
+            .collect();
+        for (cell, count) in counts {
+            if rule(self.cells.contains(&cell), count) {
+                next.insert(cell);
+            }
+        }
+        self.cells = next;
+    }
+
+    // None if no cell is alive
+    pub fn bounding_box<const N: usize>(&self) -> Option<Aabb<i32, N>> where C: Copy + Into<VecN<i32, N>> {
+        Aabb::from_points(self.cells.iter().map(|c| (*c).into()))
+    }
+
+    pub fn population_within<const N: usize>(&self, aabb: &Aabb<i32, N>) -> usize where C: Copy + Into<VecN<i32, N>> {
+        self.cells.iter().filter(|c| aabb.contains(&(**c).into())).count()
+    }
+}
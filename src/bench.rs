@@ -0,0 +1,44 @@
+use std::collections::hash_map::RandomState;
+use std::time::{Duration, Instant};
+
+use crate::dec_17;
+use crate::dec_24;
+use crate::hashing::FastBuildHasher;
+use crate::hex::Cube;
+use crate::packed::Packed64;
+use crate::vectors::VecN;
+
+const RUNS: u32 = 5;
+
+// average time of a run, the result is printed so the runs can't be optimized away
+fn time<F>(name: &str, f: F) -> Duration where F: Fn() -> usize {
+    let start = Instant::now();
+    let mut result = 0;
+    for _ in 0..RUNS {
+        result = f();
+    }
+    let average = start.elapsed() / RUNS;
+    println!("{:<32} {:>10.2?}  (result {})", name, average, result);
+    average
+}
+
+fn speedup(baseline: Duration, other: Duration) -> f64 {
+    baseline.as_secs_f64() / other.as_secs_f64()
+}
+
+// compares the default SipHash sets with the fast hasher and packed keys
+pub fn run() {
+    println!("\nDecember 17th, part two");
+    let file = "./data/dec_17.txt";
+    let sip = time("VecN<i32, 4>, SipHash", || dec_17::simulate::<VecN<i32, 4>, RandomState, 4>(file));
+    let fast = time("VecN<i32, 4>, FastHasher", || dec_17::simulate::<VecN<i32, 4>, FastBuildHasher, 4>(file));
+    let packed = time("Packed64<4>, FastHasher", || dec_17::simulate::<Packed64<4>, FastBuildHasher, 4>(file));
+    println!("speedup: {:.1}x fast hasher, {:.1}x packed keys", speedup(sip, fast), speedup(sip, packed));
+
+    println!("\nDecember 24th, part two");
+    let file = "./data/dec_24.txt";
+    let sip = time("Cube, SipHash", || dec_24::simulate::<Cube, RandomState>(file, 100));
+    let fast = time("Cube, FastHasher", || dec_24::simulate::<Cube, FastBuildHasher>(file, 100));
+    let packed = time("Packed64<3>, FastHasher", || dec_24::simulate::<Packed64<3>, FastBuildHasher>(file, 100));
+    println!("speedup: {:.1}x fast hasher, {:.1}x packed keys", speedup(sip, fast), speedup(sip, packed));
+}
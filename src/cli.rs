@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Command line options of the form --name value, --name=value or just --name for flags.
+// Every day picks the options it knows about and ignores the rest.
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    pub fn from_env() -> Self {
+        Args::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse<I>(args: I) -> Self where I: IntoIterator<Item=String> {
+        let mut result = Args::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--") {
+                Some(name) => name,
+                None => panic!("unexpected argument {}", arg),
+            };
+            if let Some((name, value)) = name.split_once('=') {
+                result.options.insert(name.to_string(), value.to_string());
+            } else if args.peek().is_some_and(|next| !next.starts_with("--")) {
+                result.options.insert(name.to_string(), args.next().unwrap());
+            } else {
+                result.flags.push(name.to_string());
+            }
+        }
+        result
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|v| v.as_str())
+    }
+
+    // None if the option is not given, panics if the value does not parse
+    pub fn get<T>(&self, name: &str) -> Option<T> where T: FromStr, T::Err: Display {
+        self.value(name)
+            .map(|v| v.parse::<T>().unwrap_or_else(|e| panic!("error: invalid value {} for --{}: {}", v, name, e)))
+    }
+
+    pub fn get_or<T>(&self, name: &str, default: T) -> T where T: FromStr, T::Err: Display {
+        self.get(name).unwrap_or(default)
+    }
+}
@@ -1,9 +1,13 @@
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Lines, Result};
 use std::path::Path;
 use std::str::FromStr;
 
-use regex::{Regex};
+use itertools::Itertools;
+use num::bigint::{BigInt, ToBigInt};
+use num::PrimInt;
+use regex::Regex;
 
 pub trait Re {
     fn re<T: FromStr>(&self, re: &str, index: usize) -> T;
@@ -25,31 +29,25 @@ impl Re for String {
 }
 
 pub fn read_numbers(filename: &str) -> Vec<i32> {
-    let mut numbers = Vec::new();
-    if let Ok(lines) = read_lines(filename) {
-        // Consumes the iterator, returns an (Optional) String
-        for line in lines {
-            if let Ok(l) = line {
-                let number = l.parse::<i32>().unwrap();
-                numbers.push(number);
-            }
-        }
-    }
-    return numbers;
+    return read_values::<i32>(filename);
 }
 
 pub fn read_larger_numbers(filename: &str) -> Vec<i64> {
-    let mut numbers = Vec::new();
+    return read_values::<i64>(filename);
+}
+
+// one value per line, e.g. read_values::<Vec2<i32>>("coordinates.txt")
+pub fn read_values<T>(filename: &str) -> Vec<T> where T: FromStr, T::Err: fmt::Debug {
+    let mut values = Vec::new();
     if let Ok(lines) = read_lines(filename) {
         // Consumes the iterator, returns an (Optional) String
         for line in lines {
             if let Ok(l) = line {
-                let number = l.parse::<i64>().unwrap();
-                numbers.push(number);
+                values.push(l.parse::<T>().unwrap());
             }
         }
     }
-    return numbers;
+    return values;
 }
 
 pub fn read_strings(filename: &str) -> Vec<String> {
@@ -88,18 +86,64 @@ pub fn read_chunks(filename: &str) -> Vec<Vec<String>> {
     return chunks;
 }
 
-pub fn format_to_sum(numbers: &Vec<i32>) -> String {
-    let strings: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
-    let joined = strings.join(" + ");
-    let sum: i32 = numbers.iter().sum();
-    return format!("{} = {}", joined, sum);
+// a sum or product that falls back to arbitrary precision once it no longer fits into T
+#[derive(Clone, Debug, PartialEq)]
+pub enum Total<T> {
+    Exact(T),
+    Big(BigInt),
+}
+
+impl<T> fmt::Display for Total<T> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Total::Exact(value) => write!(f, "{}", value),
+            Total::Big(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+pub fn checked_sum<T>(numbers: &[T]) -> Total<T> where T: PrimInt + ToBigInt {
+    let mut sum = T::zero();
+    for (i, n) in numbers.iter().enumerate() {
+        match sum.checked_add(n) {
+            Some(s) => sum = s,
+            None => {
+                let big: BigInt = numbers[i..].iter().map(|n| n.to_bigint().unwrap()).sum();
+                return Total::Big(sum.to_bigint().unwrap() + big);
+            }
+        }
+    }
+    return Total::Exact(sum);
+}
+
+pub fn checked_product<T>(numbers: &[T]) -> Total<T> where T: PrimInt + ToBigInt {
+    let mut product = T::one();
+    for (i, n) in numbers.iter().enumerate() {
+        match product.checked_mul(n) {
+            Some(p) => product = p,
+            None => {
+                let big: BigInt = numbers[i..].iter().map(|n| n.to_bigint().unwrap()).product();
+                return Total::Big(product.to_bigint().unwrap() * big);
+            }
+        }
+    }
+    return Total::Exact(product);
+}
+
+pub fn format_to_sum<T>(numbers: &[T]) -> String where T: PrimInt + ToBigInt + fmt::Display {
+    return format!("{} = {}", numbers.iter().join(" + "), checked_sum(numbers));
+}
+
+pub fn format_to_product<T>(numbers: &[T]) -> String where T: PrimInt + ToBigInt + fmt::Display {
+    return format!("{} = {}", numbers.iter().join(" * "), checked_product(numbers));
 }
 
-pub fn format_to_product(numbers: &[i32]) -> String {
-    let strings: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
-    let joined = strings.join(" * ");
-    let product: i64 = numbers.iter().map(|n| (*n as i64)).product();
-    return format!("{} = {}", joined, product);
+// explains an answer built from the smallest and the largest value of a range
+pub fn format_to_min_max_sum<T>(numbers: &[T]) -> String where T: PrimInt + ToBigInt + fmt::Display {
+    return match (numbers.iter().min(), numbers.iter().max()) {
+        (Some(min), Some(max)) => format!("min {} + max {} = {}", min, max, checked_sum(&[*min, *max])),
+        _ => "empty range".to_string(),
+    };
 }
 
 // The output is wrapped in a Result to allow matching on errors
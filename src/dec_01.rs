@@ -1,37 +1,58 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashSet;
 
+use crate::cli::Args;
 use crate::common;
+use crate::hashing::FastHashMap;
 
 const YEAR: i32 = 2020;
+const ADDENDS: usize = 3;
 
-pub fn part_one() {
+// options: --year <target sum>, --addends <number of entries for part two>,
+// --mode exact|closest|subset for part two, where closest finds the largest sum of the given number
+// of entries that does not exceed the year and subset finds entries of any number that hit it
+pub fn part_one(args: &Args) {
     println!("--- Part One ---");
 
+    let year = args.get_or("year", YEAR);
     let numbers = common::read_numbers("./data/dec_01.txt");
-    let addends = find_addends(&numbers, YEAR);
+    let addends = find_addends(&numbers, year);
     match addends {
         Some(a) => println!("Result: {}, {}",
-                            common::format_to_sum(&vec!(a.0, a.1)),
-                            common::format_to_product(&vec!(a.0, a.1))),
+                            common::format_to_sum(&[a.0, a.1]),
+                            common::format_to_product(&[a.0, a.1])),
         None => println!("Could not find addends."),
     }
 }
 
-pub fn part_two() {
+pub fn part_two(args: &Args) {
     println!("--- Part Two ---");
 
+    let year = args.get_or("year", YEAR);
+    let k = args.get_or("addends", ADDENDS);
+    if k == 0 {
+        panic!("error: --addends has to be at least 1");
+    }
     let numbers = common::read_numbers("./data/dec_01.txt");
-    for number in &numbers {
-        let addends = find_addends(&numbers, YEAR - number);
-        match addends {
-            Some(s) => println!("Result: {}, {}",
-                                common::format_to_sum(&vec!(*number, s.0, s.1)),
-                                common::format_to_product(&vec!(*number, s.0, s.1))),
-            None => {}
-        }
+    let combinations = match args.value("mode").unwrap_or("exact") {
+        "exact" => k_sum(&numbers, k, year),
+        "closest" => closest_k_sum(&numbers, k, year).into_iter().collect(),
+        "subset" => subset_sum(&numbers, year).unwrap_or_else(|e| panic!("error: {}", e)).into_iter().collect(),
+        mode => panic!("unknown mode {}", mode),
+    };
+    if combinations.is_empty() {
+        println!("Could not find addends.");
+    }
+    for indices in combinations {
+        print_addends(&numbers, &indices);
     }
 }
 
+fn print_addends(numbers: &[i32], indices: &[usize]) {
+    let addends: Vec<i32> = indices.iter().map(|i| numbers[*i]).collect();
+    println!("Result: {}, {}", common::format_to_sum(&addends), common::format_to_product(&addends));
+}
+
 fn find_addends(numbers: &Vec<i32>, sum: i32) -> Option<(i32, i32)> {
     let mut numbers_set = HashSet::new();
     for number in numbers {
@@ -44,3 +65,248 @@ fn find_addends(numbers: &Vec<i32>, sum: i32) -> Option<(i32, i32)> {
     }
     return None;
 }
+
+// All distinct combinations of k entries that sum up to target, as ascending indices into numbers.
+// No entry is used twice, but equal numbers at different indices count as different entries.
+// There are none for k = 0, the empty combination does not count.
+pub fn k_sum(numbers: &[i32], k: usize, target: i32) -> Vec<Vec<usize>> {
+    let mut combinations = Vec::new();
+    KSum::new(numbers).search(k, target, |indices| {
+        combinations.push(indices);
+        false
+    });
+    combinations
+}
+
+pub fn k_sum_first(numbers: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+    let mut first = None;
+    KSum::new(numbers).search(k, target, |indices| {
+        first = Some(indices);
+        true
+    });
+    first
+}
+
+// The combination of k entries with the largest sum that does not exceed target, None if even
+// the k smallest entries are too large or k = 0.
+pub fn closest_k_sum(numbers: &[i32], k: usize, target: i32) -> Option<Vec<usize>> {
+    if k == 0 {
+        return None;
+    }
+    let search = KSum::new(numbers);
+    let mut chosen = Vec::with_capacity(k);
+    let mut best = None;
+    search.closest_from(0, k, target as i64, 0, &mut chosen, &mut best);
+    best.map(|(_, indices)| indices)
+}
+
+// largest number of distinct sums subset_sum keeps track of
+const MAX_SUBSET_SUMS: usize = 1 << 22;
+
+// At least one entry, but otherwise any number of them, that sum up to target, as ascending indices. Dynamic programming over
+// the sums reached so far, so it takes time and memory proportional to the entries times the
+// number of distinct sums, which never exceeds the range between the negative and positive total.
+// Err if there are more than MAX_SUBSET_SUMS of them.
+pub fn subset_sum(numbers: &[i32], target: i32) -> Result<Option<Vec<usize>>, String> {
+    let low: i64 = numbers.iter().filter(|n| **n < 0).map(|n| *n as i64).sum();
+    let high: i64 = numbers.iter().filter(|n| **n > 0).map(|n| *n as i64).sum();
+    let target = target as i64;
+    if target < low || target > high {
+        return Ok(None);
+    }
+
+    // the entry that first reached each sum and the sum of the earlier entries it was added to,
+    // None if it was taken on its own; the empty subset does not count
+    let mut reached_by: FastHashMap<i64, (usize, Option<i64>)> = FastHashMap::default();
+    let mut sums: Vec<i64> = Vec::new();
+    for (i, number) in numbers.iter().enumerate() {
+        if reached_by.contains_key(&target) {
+            break;
+        }
+        let number = *number as i64;
+        let candidates = std::iter::once((number, None)).chain(sums.iter().map(|s| (s + number, Some(*s))));
+        let new_sums: Vec<(i64, Option<i64>)> = candidates.filter(|(s, _)| !reached_by.contains_key(s)).collect();
+        for (sum, previous) in new_sums {
+            if let Entry::Vacant(entry) = reached_by.entry(sum) {
+                entry.insert((i, previous));
+                sums.push(sum);
+            }
+        }
+        if sums.len() > MAX_SUBSET_SUMS {
+            return Err(format!("the entries reach more than {} distinct sums, too many for subset sum", MAX_SUBSET_SUMS));
+        }
+    }
+
+    // every entry was added to a sum reached by earlier entries only
+    let mut indices = Vec::new();
+    let mut next = Some(target);
+    while let Some(sum) = next {
+        let (i, previous) = match reached_by.get(&sum) {
+            Some(entry) => *entry,
+            None => return Ok(None),
+        };
+        indices.push(i);
+        next = previous;
+    }
+    indices.reverse();
+    Ok(Some(indices))
+}
+
+// The entries sorted by value: the first k - 2 entries are fixed one after another, pruning
+// whenever the target is out of reach, and the last two are found with two pointers.
+struct KSum {
+    values: Vec<i64>,
+    indices: Vec<usize>,
+    // prefix[i] is the sum of the i smallest values
+    prefix: Vec<i64>,
+}
+
+impl KSum {
+    fn new(numbers: &[i32]) -> Self {
+        let mut indices: Vec<usize> = (0..numbers.len()).collect();
+        indices.sort_by_key(|i| numbers[*i]);
+        let values: Vec<i64> = indices.iter().map(|i| numbers[*i] as i64).collect();
+        let mut prefix = vec![0];
+        prefix.extend(values.iter().scan(0, |sum, v| {
+            *sum += v;
+            Some(*sum)
+        }));
+        KSum { values, indices, prefix }
+    }
+
+    // visit gets every combination and returns whether to stop
+    fn search<F>(&self, k: usize, target: i32, mut visit: F) where F: FnMut(Vec<usize>) -> bool {
+        if k == 0 {
+            return;
+        }
+        let mut chosen = Vec::with_capacity(k);
+        self.search_from(0, k, target as i64, &mut chosen, &mut visit);
+    }
+
+    fn sum(&self, from: usize, to: usize) -> i64 {
+        self.prefix[to] - self.prefix[from]
+    }
+
+    // chosen and start are positions in the sorted values; returns true once visit asked to stop
+    fn search_from<F>(&self, start: usize, k: usize, target: i64, chosen: &mut Vec<usize>, visit: &mut F) -> bool where F: FnMut(Vec<usize>) -> bool {
+        let n = self.values.len();
+        if k == 0 {
+            return target == 0 && self.report(chosen, &[], visit);
+        }
+        if n - start < k {
+            return false;
+        }
+        if k == 2 {
+            return self.two_pointers(start, target, chosen, visit);
+        }
+        for i in start..=n - k {
+            // even the smallest remaining entries are too large
+            if self.sum(i, i + k) > target {
+                break;
+            }
+            // even the largest remaining entries are too small
+            if self.values[i] + self.sum(n - k + 1, n) < target {
+                continue;
+            }
+            chosen.push(i);
+            let stop = self.search_from(i + 1, k - 1, target - self.values[i], chosen, visit);
+            chosen.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn two_pointers<F>(&self, start: usize, target: i64, chosen: &[usize], visit: &mut F) -> bool where F: FnMut(Vec<usize>) -> bool {
+        let values = &self.values;
+        let (mut lo, mut hi) = (start, values.len() - 1);
+        while lo < hi {
+            let sum = values[lo] + values[hi];
+            if sum < target {
+                lo += 1;
+            } else if sum > target {
+                hi -= 1;
+            } else if values[lo] == values[hi] {
+                // everything in between is equal as well, any two of them match
+                for a in lo..hi {
+                    for b in a + 1..=hi {
+                        if self.report(chosen, &[a, b], visit) {
+                            return true;
+                        }
+                    }
+                }
+                return false;
+            } else {
+                // pair every copy of the low value with every copy of the high value
+                let lo_end = (lo..hi).find(|i| values[*i] != values[lo]).unwrap_or(hi);
+                let hi_start = (lo_end..hi).rev().find(|i| values[*i] != values[hi]).map_or(lo_end, |i| i + 1);
+                for a in lo..lo_end {
+                    for b in hi_start..=hi {
+                        if self.report(chosen, &[a, b], visit) {
+                            return true;
+                        }
+                    }
+                }
+                lo = lo_end;
+                hi = hi_start - 1;
+            }
+        }
+        false
+    }
+
+    // like search_from, but keeps the best sum not above target, sum is the sum of chosen so far
+    fn closest_from(&self, start: usize, k: usize, target: i64, sum: i64, chosen: &mut Vec<usize>, best: &mut Option<(i64, Vec<usize>)>) {
+        let n = self.values.len();
+        if best.as_ref().is_some_and(|(b, _)| *b == target) {
+            return;
+        }
+        if k == 0 {
+            if sum <= target && best.as_ref().is_none_or(|(b, _)| sum > *b) {
+                *best = Some((sum, self.report_indices(chosen, &[])));
+            }
+            return;
+        }
+        if n - start < k {
+            return;
+        }
+        if k == 2 {
+            let (mut lo, mut hi) = (start, n - 1);
+            while lo < hi {
+                let pair = sum + self.values[lo] + self.values[hi];
+                if pair > target {
+                    hi -= 1;
+                    continue;
+                }
+                if best.as_ref().is_none_or(|(b, _)| pair > *b) {
+                    *best = Some((pair, self.report_indices(chosen, &[lo, hi])));
+                }
+                lo += 1;
+            }
+            return;
+        }
+        for i in start..=n - k {
+            // even the smallest remaining entries are too large
+            if sum + self.sum(i, i + k) > target {
+                break;
+            }
+            // even the largest remaining entries can't beat the best so far
+            if best.as_ref().is_some_and(|(b, _)| sum + self.values[i] + self.sum(n - k + 1, n) <= *b) {
+                continue;
+            }
+            chosen.push(i);
+            self.closest_from(i + 1, k - 1, target, sum + self.values[i], chosen, best);
+            chosen.pop();
+        }
+    }
+
+    fn report_indices(&self, chosen: &[usize], last: &[usize]) -> Vec<usize> {
+        let mut indices: Vec<usize> = chosen.iter().chain(last).map(|p| self.indices[*p]).collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    fn report<F>(&self, chosen: &[usize], last: &[usize], visit: &mut F) -> bool where F: FnMut(Vec<usize>) -> bool {
+        visit(self.report_indices(chosen, last))
+    }
+}
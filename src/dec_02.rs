@@ -1,65 +1,292 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
+use crate::cli::Args;
 use crate::common;
+use crate::pattern::line_pattern;
 
-struct PasswordEntry {
-    number_1: i32,
-    number_2: i32,
-    character: char,
-    password: String,
+line_pattern! {
+    #[pattern("{}-{} {}: {}")]
+    struct PasswordEntry {
+        number_1: i32,
+        number_2: i32,
+        character: char,
+        password: String,
+    }
 }
 
 impl PasswordEntry {
     fn parse(string: &str) -> PasswordEntry {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"^(\d+)-(\d+) (\w): (\w+)$").unwrap();
+        return string.parse::<PasswordEntry>().unwrap_or_else(|e| panic!("error: {}", e));
+    }
+
+    fn count(&self) -> i32 {
+        self.password.chars().filter(|c| *c == self.character).count() as i32
+    }
+
+    // positions start at 1, None if there is no such position
+    fn char_at(&self, position: i32) -> Option<char> {
+        if position < 1 {
+            return None;
         }
+        self.password.chars().nth((position - 1) as usize)
+    }
 
-        assert!(RE.is_match(string));
-        let captures = RE.captures(string);
-        assert!(captures.is_some());
-        let c = captures.unwrap();
-        assert_eq!(c.len(), 5);
-        return PasswordEntry {
-            number_1: (&c[1]).parse::<i32>().unwrap(),
-            number_2: (&c[2]).parse::<i32>().unwrap(),
-            character: (&c[3]).parse::<char>().unwrap(),
-            password: String::from(&c[4]),
-        };
+    fn describe_position(&self, position: i32) -> String {
+        match self.char_at(position) {
+            Some(c) => format!("position {} is '{}'", position, c),
+            None => format!("position {} is out of range", position),
+        }
     }
+}
 
-    fn is_valid_part_one(&self) -> bool {
-        let count = self.password.chars().into_iter()
-            .filter(|c| *c == self.character)
-            .count() as i32;
-        return count >= self.number_1 && count <= self.number_2;
+// Policies ------------------------------------------------------
+
+trait PasswordPolicy {
+    fn name(&self) -> String;
+
+    // the reason the entry violates the policy, if it does
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String>;
+}
+
+// the character has to occur number_1 to number_2 times
+struct CountInRange;
+
+impl PasswordPolicy for CountInRange {
+    fn name(&self) -> String {
+        "count in range".to_string()
     }
 
-    fn is_valid_part_two(&self) -> bool {
-        let chars: Vec<char> = self.password.chars().collect();
-        let first_match = chars[(self.number_1 - 1) as usize] == self.character;
-        let second_match = chars[(self.number_2 - 1) as usize] == self.character;
-        return first_match ^ second_match;
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let count = entry.count();
+        if count >= entry.number_1 && count <= entry.number_2 {
+            Ok(())
+        } else {
+            Err(format!("'{}' occurs {} times, expected {} to {}", entry.character, count, entry.number_1, entry.number_2))
+        }
     }
 }
 
-pub fn part_one() {
-    println!("--- Part One ---");
+// the character has to be at exactly one of the positions number_1 and number_2
+struct ExactlyOnePosition;
+
+impl PasswordPolicy for ExactlyOnePosition {
+    fn name(&self) -> String {
+        "exactly one position".to_string()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let first_match = entry.char_at(entry.number_1) == Some(entry.character);
+        let second_match = entry.char_at(entry.number_2) == Some(entry.character);
+        if first_match ^ second_match {
+            return Ok(());
+        }
+        Err(format!("{} and {}, expected exactly one '{}'",
+                    entry.describe_position(entry.number_1), entry.describe_position(entry.number_2), entry.character))
+    }
+}
+
+struct Length {
+    min: Option<usize>,
+    max: Option<usize>,
+}
+
+impl PasswordPolicy for Length {
+    fn name(&self) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("length {}-{}", min, max),
+            (Some(min), None) => format!("length >= {}", min),
+            (None, Some(max)) => format!("length <= {}", max),
+            (None, None) => "any length".to_string(),
+        }
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let length = entry.password.chars().count();
+        if self.min.is_some_and(|min| length < min) || self.max.is_some_and(|max| length > max) {
+            Err(format!("length is {}, expected {}", length, self.name()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CharClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+}
+
+impl CharClass {
+    fn parse(string: &str) -> CharClass {
+        match string {
+            "lower" => CharClass::Lowercase,
+            "upper" => CharClass::Uppercase,
+            "digit" => CharClass::Digit,
+            "symbol" => CharClass::Symbol,
+            _ => panic!("unknown character class {}, expected lower, upper, digit or symbol", string),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            CharClass::Lowercase => "lowercase letter",
+            CharClass::Uppercase => "uppercase letter",
+            CharClass::Digit => "digit",
+            CharClass::Symbol => "symbol",
+        }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        match self {
+            CharClass::Lowercase => c.is_lowercase(),
+            CharClass::Uppercase => c.is_uppercase(),
+            CharClass::Digit => c.is_ascii_digit(),
+            CharClass::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+}
+
+// at least one character of every class
+struct RequiredClasses(Vec<CharClass>);
+
+impl PasswordPolicy for RequiredClasses {
+    fn name(&self) -> String {
+        format!("requires {}", self.0.iter().map(|c| c.name()).collect::<Vec<&str>>().join(", "))
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let missing: Vec<&str> = self.0.iter()
+            .filter(|class| !entry.password.chars().any(|c| class.contains(c)))
+            .map(|class| class.name())
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("no {}", missing.join(", no ")))
+        }
+    }
+}
+
+struct ForbiddenChars(String);
+
+impl PasswordPolicy for ForbiddenChars {
+    fn name(&self) -> String {
+        format!("forbids \"{}\"", self.0)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let mut found: Vec<char> = entry.password.chars().filter(|c| self.0.contains(*c)).collect();
+        found.sort_unstable();
+        found.dedup();
+        if found.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("contains forbidden \"{}\"", found.into_iter().collect::<String>()))
+        }
+    }
+}
+
+// every policy has to hold, all violations are reported
+struct AllOf(Vec<Box<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for AllOf {
+    fn name(&self) -> String {
+        self.0.iter().map(|p| p.name()).collect::<Vec<String>>().join(" and ")
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Result<(), String> {
+        let reasons: Vec<String> = self.0.iter().filter_map(|p| p.check(entry).err()).collect();
+        if reasons.is_empty() {
+            Ok(())
+        } else {
+            Err(reasons.join("; "))
+        }
+    }
+}
+
+// Audit ---------------------------------------------------------
+
+struct Verdict<'a> {
+    entry: &'a PasswordEntry,
+    result: Result<(), String>,
+}
+
+fn judge<'a>(entries: &'a [PasswordEntry], policy: &dyn PasswordPolicy) -> Vec<Verdict<'a>> {
+    entries.iter().map(|entry| Verdict { entry, result: policy.check(entry) }).collect()
+}
+
+fn count_valid(entries: &[PasswordEntry], policy: &dyn PasswordPolicy) -> usize {
+    judge(entries, policy).iter().filter(|v| v.result.is_ok()).count()
+}
 
-    let count: usize = common::read_strings("./data/dec_02.txt").iter()
+fn read_entries() -> Vec<PasswordEntry> {
+    common::read_strings("./data/dec_02.txt").iter()
         .map(|s| PasswordEntry::parse(s))
-        .filter(|p| p.is_valid_part_one())
-        .count();
-    println!("Result: {}", count)
+        .collect()
+}
+
+// The two policies of the puzzle, plus a custom one if any of its options are given:
+// --min-length <n>, --max-length <n>, --require <lower,upper,digit,symbol>, --forbid <characters>.
+// --verbose lists every rejected entry with its reason.
+fn policies(args: &Args) -> Vec<Box<dyn PasswordPolicy>> {
+    let mut custom: Vec<Box<dyn PasswordPolicy>> = Vec::new();
+    let min = args.get::<usize>("min-length");
+    let max = args.get::<usize>("max-length");
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            panic!("error: --min-length {} is larger than --max-length {}", min, max);
+        }
+    }
+    if min.is_some() || max.is_some() {
+        custom.push(Box::new(Length { min, max }));
+    }
+    if let Some(classes) = args.value("require") {
+        custom.push(Box::new(RequiredClasses(classes.split(',').map(CharClass::parse).collect())));
+    }
+    if let Some(forbidden) = args.value("forbid") {
+        custom.push(Box::new(ForbiddenChars(forbidden.to_string())));
+    }
+
+    let mut policies: Vec<Box<dyn PasswordPolicy>> = vec![Box::new(CountInRange), Box::new(ExactlyOnePosition)];
+    if !custom.is_empty() {
+        policies.push(Box::new(AllOf(custom)));
+    }
+    policies
+}
+
+fn print_audit(entries: &[PasswordEntry], policies: &[Box<dyn PasswordPolicy>], verbose: bool) {
+    let width = policies.iter().map(|p| p.name().len()).max().unwrap_or(0).max("Policy".len());
+    println!("{:<width$} | {:>7} | {:>7}", "Policy", "Valid", "Invalid", width = width);
+    println!("{}-+-{}-+-{}", "-".repeat(width), "-".repeat(7), "-".repeat(7));
+    for policy in policies {
+        let valid = count_valid(entries, policy.as_ref());
+        println!("{:<width$} | {:>7} | {:>7}", policy.name(), valid, entries.len() - valid, width = width);
+    }
+    if verbose {
+        for policy in policies {
+            println!("\n{}:", policy.name());
+            for verdict in judge(entries, policy.as_ref()) {
+                if let Err(reason) = verdict.result {
+                    let e = verdict.entry;
+                    println!("  {}-{} {}: {}  ->  {}", e.number_1, e.number_2, e.character, e.password, reason);
+                }
+            }
+        }
+    }
+}
+
+pub fn part_one() {
+    println!("--- Part One ---");
+    println!("Result: {}", count_valid(&read_entries(), &CountInRange))
 }
 
 pub fn part_two() {
     println!("--- Part Two ---");
+    println!("Result: {}", count_valid(&read_entries(), &ExactlyOnePosition))
+}
 
-    let count: usize = common::read_strings("./data/dec_02.txt").iter()
-        .map(|s| PasswordEntry::parse(s))
-        .filter(|p| p.is_valid_part_two())
-        .count();
-    println!("Result: {}", count)
+// options are described at policies
+pub fn audit(args: &Args) {
+    println!("--- Audit ---");
+    print_audit(&read_entries(), &policies(args), args.flag("verbose"));
 }
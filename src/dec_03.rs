@@ -1,12 +1,55 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::aabb::Aabb;
+use crate::cli::Args;
 use crate::common;
+use crate::vectors::Vec2;
 
 struct Landscape {
     grid: Vec<Vec<i32>>
 }
 
+// steps right (negative for left) and down per move, the map repeats to the left and right
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Slope {
-    x: usize,
-    y: usize,
+    x: i32,
+    y: i32,
+}
+
+impl Slope {
+    // a slope that does not go down never reaches the bottom
+    fn new(x: i32, y: i32) -> Result<Slope, String> {
+        if y <= 0 {
+            return Err(format!("degenerate slope {}, it has to go down", Slope { x, y }));
+        }
+        Ok(Slope { x, y })
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "right {}, down {}", self.x, self.y)
+    }
+}
+
+// either x,y or right/down like 3/2, which is not reduced: 2/4 steps four rows at a time
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((x, y)) = s.split_once('/') {
+            let parse = |n: &str| n.trim().parse::<i32>().map_err(|e| format!("{}: {}", s, e));
+            return Slope::new(parse(x)?, parse(y)?);
+        }
+        let v = s.parse::<Vec2<i32>>()?;
+        Slope::new(v.x, v.y)
+    }
+}
+
+struct SearchResult {
+    fewest: (i32, Vec<Slope>),
+    most: (i32, Vec<Slope>),
 }
 
 impl Landscape {
@@ -31,19 +74,70 @@ impl Landscape {
         };
     }
 
+    fn width(&self) -> i32 {
+        self.grid[0].len() as i32
+    }
+
+    fn height(&self) -> i32 {
+        self.grid.len() as i32
+    }
+
+    fn is_tree(&self, pos: Vec2<i32>) -> bool {
+        self.grid[pos.y as usize][pos.x as usize] == 1
+    }
+
+    // every visited position from the top left corner to the bottom, x wrapped into the map
+    fn path(&self, slope: &Slope) -> Vec<Vec2<i32>> {
+        let mut path = Vec::new();
+        let mut pos = Vec2::new(0, 0);
+        while pos.y < self.height() {
+            path.push(pos);
+            pos = Vec2::new((pos.x + slope.x).rem_euclid(self.width()), pos.y + slope.y);
+        }
+        return path;
+    }
+
     fn evaluate(&self, slope: &Slope) -> i32 {
-        let height = self.grid.len();
-        let width = self.grid[0].len();
-
-        let mut x = 0;
-        let mut y = 0;
-        let mut count = 0;
-        while y < height {
-            count += self.grid[y][x];
-            x = (x + slope.x) % width;
-            y = y + slope.y;
+        return self.path(slope).iter().filter(|p| self.is_tree(**p)).count() as i32;
+    }
+
+    // all slopes with at most bound steps either way, there are none for a bound below 1
+    fn search(&self, bound: i32) -> Result<SearchResult, String> {
+        if bound < 1 {
+            return Err(format!("search bound {} leaves no slopes, it has to be at least 1", bound));
+        }
+        let mut result = SearchResult { fewest: (i32::MAX, Vec::new()), most: (i32::MIN, Vec::new()) };
+        for y in 1..=bound {
+            for x in -bound..=bound {
+                let slope = Slope { x, y };
+                let trees = self.evaluate(&slope);
+                if trees < result.fewest.0 {
+                    result.fewest = (trees, Vec::new());
+                }
+                if trees == result.fewest.0 {
+                    result.fewest.1.push(slope);
+                }
+                if trees > result.most.0 {
+                    result.most = (trees, Vec::new());
+                }
+                if trees == result.most.0 {
+                    result.most.1.push(slope);
+                }
+            }
         }
-        return count;
+        return Ok(result);
+    }
+
+    // the path is marked with O on open squares and X on trees
+    fn render(&self, slope: &Slope) -> String {
+        let path = self.path(slope);
+        let bounds = Aabb::new(Vec2::new(0, 0), Vec2::new(self.width() - 1, self.height() - 1));
+        return bounds.render(|pos| match (path.contains(&pos), self.is_tree(pos)) {
+            (true, true) => 'X',
+            (true, false) => 'O',
+            (false, true) => '#',
+            (false, false) => '.',
+        });
     }
 }
 
@@ -69,3 +163,23 @@ pub fn part_two() {
         .collect::<Vec<i32>>();
     println!("Result: {}", common::format_to_product(&results));
 }
+
+// options: --slope <x,y or ratio> renders the path of a slope, --search <bound> finds the slopes
+// with the fewest and most trees
+pub fn explore(args: &Args) {
+    println!("--- Explore ---");
+
+    let landscape = Landscape::from_file("./data/dec_03.txt");
+    if let Some(slope) = args.value("slope") {
+        let slope = slope.parse::<Slope>().unwrap_or_else(|e| panic!("error: {}", e));
+        print!("{}", landscape.render(&slope));
+        println!("Trees for {}: {}", slope, landscape.evaluate(&slope));
+    }
+    if let Some(bound) = args.get::<i32>("search") {
+        let result = landscape.search(bound).unwrap_or_else(|e| panic!("error: {}", e));
+        for (label, (trees, slopes)) in [("Fewest", result.fewest), ("Most", result.most)] {
+            let slopes: Vec<String> = slopes.iter().map(|s| format!("({}, {})", s.x, s.y)).collect();
+            println!("{} trees: {} with {}", label, trees, slopes.join(", "));
+        }
+    }
+}
@@ -1,33 +1,28 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
 
-use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::cli::Args;
 use crate::common;
 
+const SCHEMA: &str = "./data/dec_04_schema.txt";
+
 struct Passport {
     fields: HashMap<String, String>
 }
 
 impl Passport {
+    // passports are separated by blank lines
     fn from_file(filename: &str) -> Vec<Passport> {
-        let lines = common::read_strings(filename);
-        let mut passports = Vec::new();
-        let mut entry = "".to_string();
-        for line in lines {
-            if line.trim().is_empty() {
-                passports.push(Passport::parse(&entry));
-                entry = "".to_string();
-            } else {
-                entry.push_str(&line.trim());
-                entry.push_str(" ");
-            }
-        }
-        passports.push(Passport::parse(&entry));
-        return passports;
+        return common::read_chunks(filename).iter()
+            .filter(|chunk| !chunk.is_empty())
+            .map(|chunk| Passport::parse(&chunk.join(" ")))
+            .collect();
     }
 
-    fn parse(string: &String) -> Passport {
+    fn parse(string: &str) -> Passport {
         let field_strings = string.trim().split_whitespace();
         let mut fields = HashMap::new();
         for field_string in field_strings {
@@ -37,89 +32,346 @@ impl Passport {
         return Passport { fields };
     }
 
-    fn fields_present(&self) -> bool {
-        let mandatory_fields = vec!("byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid");
-        let optional_fields = vec!("cid");
+    // sorted by key
+    fn sorted_fields(&self) -> Vec<(&String, &String)> {
+        let mut fields: Vec<(&String, &String)> = self.fields.iter().collect();
+        fields.sort();
+        return fields;
+    }
+}
+
+// Schema --------------------------------------------------------
 
-        let mut copy = HashMap::new();
-        copy.extend(self.fields.iter());
+#[derive(Clone, Debug)]
+enum Rule {
+    Any,
+    Int { min: i64, max: i64 },
+    // unit, min, max
+    Units(Vec<(String, i64, i64)>),
+    // the compiled regex and the pattern as written
+    Regex(Regex, String),
+    Enum(Vec<String>),
+}
 
-        for m in mandatory_fields {
-            match copy.remove(&m.to_string()) {
-                Some(_v) => {}
-                None => { return false; }
+impl Rule {
+    fn parse(name: &str, arguments: &[&str]) -> Result<Rule, String> {
+        let number = |s: &str| s.parse::<i64>().map_err(|e| format!("invalid number {}: {}", s, e));
+        match (name, arguments.len()) {
+            ("any", 0) => Ok(Rule::Any),
+            ("int", 2) => Ok(Rule::Int { min: number(arguments[0])?, max: number(arguments[1])? }),
+            ("units", n) if n > 0 && n % 3 == 0 => {
+                let units = arguments.chunks(3)
+                    .map(|u| Ok((u[0].to_string(), number(u[1])?, number(u[2])?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(Rule::Units(units))
             }
+            ("regex", 1) => Regex::new(&format!("^(?:{})$", arguments[0]))
+                .map(|regex| Rule::Regex(regex, arguments[0].to_string()))
+                .map_err(|e| format!("invalid regex {}: {}", arguments[0], e)),
+            ("enum", n) if n > 0 => Ok(Rule::Enum(arguments.iter().map(|a| a.to_string()).collect())),
+            _ => Err(format!("invalid rule {} with {} arguments", name, arguments.len())),
         }
-        for o in optional_fields {
-            copy.remove(&o.to_string());
+    }
+
+    fn check(&self, value: &str) -> Result<(), String> {
+        let in_range = |n: i64, min: i64, max: i64, unit: &str| {
+            if n >= min && n <= max {
+                Ok(())
+            } else {
+                Err(format!("{}{} is not within {}{}..={}{}", n, unit, min, unit, max, unit))
+            }
+        };
+        match self {
+            Rule::Any => Ok(()),
+            Rule::Int { min, max } => match value.parse::<i64>() {
+                Ok(n) => in_range(n, *min, *max, ""),
+                Err(_) => Err(format!("{} is not a number", value)),
+            },
+            Rule::Units(units) => {
+                let digits = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+                let (number, unit) = value.split_at(digits);
+                let n = number.parse::<i64>().map_err(|_| format!("{} does not start with a number", value))?;
+                match units.iter().find(|u| u.0 == unit) {
+                    Some((unit, min, max)) => in_range(n, *min, *max, unit),
+                    None => Err(format!("{} has none of the units {}", value, units.iter().map(|u| u.0.as_str()).collect::<Vec<&str>>().join(", "))),
+                }
+            }
+            Rule::Regex(regex, pattern) => {
+                if regex.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(format!("{} does not match {}", value, pattern))
+                }
+            }
+            Rule::Enum(values) => {
+                if values.iter().any(|v| v == value) {
+                    Ok(())
+                } else {
+                    Err(format!("{} is not one of {}", value, values.join(", ")))
+                }
+            }
         }
-        return copy.len() == 0;
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FieldSpec {
+    name: String,
+    required: bool,
+    rule: Rule,
+}
+
+// why a passport is invalid, one per failing field
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Failure {
+    field: String,
+    reason: String,
+}
+
+impl Failure {
+    fn new(field: &str, reason: &str) -> Failure {
+        Failure { field: field.to_string(), reason: reason.to_string() }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
+
+// the format is described in data/dec_04_schema.txt
+#[derive(Clone, Debug)]
+struct Schema {
+    fields: Vec<FieldSpec>,
+    reject_unknown: bool,
+}
+
+impl Schema {
+    fn from_file(filename: &str) -> Schema {
+        let lines = common::read_strings(filename);
+        return Schema::parse(&lines).unwrap_or_else(|e| panic!("error in {}: {}", filename, e));
     }
 
-    fn fields_valid(&self) -> bool {
-        for field in &self.fields {
-            if !self.field_valid(field.0, field.1) {
-                return false;
+    fn parse(lines: &[String]) -> Result<Schema, String> {
+        let mut schema = Schema { fields: Vec::new(), reject_unknown: false };
+        for (i, line) in lines.iter().enumerate() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let error = |message: String| format!("line {}: {}", i + 1, message);
+            match words.as_slice() {
+                [] => {}
+                [comment, ..] if comment.starts_with('#') => {}
+                ["unknown", "reject"] => schema.reject_unknown = true,
+                ["unknown", "ignore"] => schema.reject_unknown = false,
+                [name, presence, rule, arguments @ ..] => {
+                    let required = match *presence {
+                        "required" => true,
+                        "optional" => false,
+                        _ => return Err(error(format!("expected required or optional, found {}", presence))),
+                    };
+                    if schema.fields.iter().any(|f| f.name == *name) {
+                        return Err(error(format!("field {} is defined twice", name)));
+                    }
+                    let rule = Rule::parse(rule, arguments).map_err(error)?;
+                    schema.fields.push(FieldSpec { name: name.to_string(), required, rule });
+                }
+                _ => return Err(error(format!("cannot parse \"{}\"", line))),
             }
         }
-        return true;
+        return Ok(schema);
     }
 
-    fn field_valid(&self, key: &String, value: &String) -> bool {
-        lazy_static! {
-            static ref HGT_CM: Regex = Regex::new(r"^(\d{3})cm$").unwrap();
-            static ref HGT_IN: Regex = Regex::new(r"^(\d{2})in$").unwrap();
-            static ref PID: Regex = Regex::new(r"^\d{9}$").unwrap();
-            static ref HCL: Regex = Regex::new(r"^#[0-9a-f]{6}$").unwrap();
-            static ref ECL: Regex = Regex::new(r"^(amb|blu|brn|gry|grn|hzl|oth)$").unwrap();
+    // missing required fields and unknown fields, sorted by field
+    fn check_fields(&self, passport: &Passport) -> Vec<Failure> {
+        let mut failures: Vec<Failure> = self.fields.iter()
+            .filter(|spec| spec.required && !passport.fields.contains_key(&spec.name))
+            .map(|spec| Failure::new(&spec.name, "missing"))
+            .collect();
+        if self.reject_unknown {
+            failures.extend(passport.fields.keys()
+                .filter(|key| !self.fields.iter().any(|spec| spec.name == **key))
+                .map(|key| Failure::new(key, "unknown field")));
         }
+        failures.sort_by(|a, b| a.field.cmp(&b.field));
+        return failures;
+    }
 
-        return match key.as_str() {
-            "byr" => { Passport::is_number_between(value, 1920, 2002) }
-            "iyr" => { Passport::is_number_between(value, 2010, 2020) }
-            "eyr" => { Passport::is_number_between(value, 2020, 2030) }
-            "hgt" => {
-                if HGT_CM.is_match(value) {
-                    Passport::is_number_between(&HGT_CM.captures(value).unwrap()[1].to_string(), 150, 193)
-                } else if HGT_IN.is_match(value) {
-                    Passport::is_number_between(&HGT_IN.captures(value).unwrap()[1].to_string(), 59, 76)
-                } else {
-                    false
+    // check_fields plus every known field whose value breaks its rule
+    fn validate(&self, passport: &Passport) -> Vec<Failure> {
+        let mut failures = self.check_fields(passport);
+        for spec in &self.fields {
+            if let Some(value) = passport.fields.get(&spec.name) {
+                if let Err(reason) = spec.rule.check(value) {
+                    failures.push(Failure::new(&spec.name, &reason));
                 }
             }
-            "pid" => { PID.is_match(value) }
-            "hcl" => { HCL.is_match(value) }
-            "ecl" => { ECL.is_match(value) }
-            "cid" => { true }
-            _ => { false }
-        };
+        }
+        failures.sort_by(|a, b| a.field.cmp(&b.field));
+        return failures;
     }
+}
 
-    fn is_number_between(string: &String, min: i32, max: i32) -> bool {
-        return match string.parse::<i32>() {
-            Ok(n) => { n >= min && n <= max }
-            Err(_e) => { false }
-        };
+// Export --------------------------------------------------------
+
+fn json_string(s: &str) -> String {
+    let mut result = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
     }
+    result.push('"');
+    return result;
 }
 
-pub fn part_one() {
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        return format!("\"{}\"", s.replace('"', "\"\""));
+    }
+    return s.to_string();
+}
+
+// one object per passport: {"fields":{...},"valid":...,"reasons":[...]}
+fn to_json_lines(passports: &[Passport], schema: &Schema) -> String {
+    let mut result = String::new();
+    for passport in passports {
+        let failures = schema.validate(passport);
+        let fields: Vec<String> = passport.sorted_fields().iter()
+            .map(|(k, v)| format!("{}:{}", json_string(k), json_string(v)))
+            .collect();
+        let reasons: Vec<String> = failures.iter().map(|f| json_string(&f.to_string())).collect();
+        result.push_str(&format!("{{\"fields\":{{{}}},\"valid\":{},\"reasons\":[{}]}}\n",
+                                 fields.join(","), failures.is_empty(), reasons.join(",")));
+    }
+    return result;
+}
+
+// the schema fields in order, then any unknown fields sorted, then valid and reasons
+fn to_csv(passports: &[Passport], schema: &Schema) -> String {
+    let mut columns: Vec<String> = schema.fields.iter().map(|f| f.name.clone()).collect();
+    let mut unknown: Vec<String> = passports.iter()
+        .flat_map(|p| p.fields.keys())
+        .filter(|key| !columns.contains(key))
+        .cloned()
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    columns.extend(unknown);
+
+    let mut result = format!("{},valid,reasons\n", columns.iter().map(|c| csv_field(c)).collect::<Vec<String>>().join(","));
+    for passport in passports {
+        let failures = schema.validate(passport);
+        let mut row: Vec<String> = columns.iter()
+            .map(|c| csv_field(passport.fields.get(c).map(|v| v.as_str()).unwrap_or("")))
+            .collect();
+        row.push(failures.is_empty().to_string());
+        row.push(csv_field(&failures.iter().map(|f| f.to_string()).collect::<Vec<String>>().join("; ")));
+        result.push_str(&format!("{}\n", row.join(",")));
+    }
+    return result;
+}
+
+// Duplicates ----------------------------------------------------
+
+// Groups of passport indices: passports with exactly the same fields, and passports that share a
+// pid but differ in some other field.
+fn find_duplicates(passports: &[Passport]) -> (Vec<Vec<usize>>, Vec<Vec<usize>>) {
+    let mut identical: HashMap<Vec<(&String, &String)>, Vec<usize>> = HashMap::new();
+    let mut by_pid: HashMap<&String, Vec<usize>> = HashMap::new();
+    for (i, passport) in passports.iter().enumerate() {
+        identical.entry(passport.sorted_fields()).or_default().push(i);
+        if let Some(pid) = passport.fields.get("pid") {
+            by_pid.entry(pid).or_default().push(i);
+        }
+    }
+
+    let mut exact: Vec<Vec<usize>> = identical.into_values().filter(|group| group.len() > 1).collect();
+    exact.sort();
+    let mut near: Vec<Vec<usize>> = by_pid.into_values()
+        .filter(|group| group.len() > 1)
+        .filter(|group| !exact.contains(group))
+        .collect();
+    near.sort();
+    return (exact, near);
+}
+
+// the fields with different values across the group
+fn differing_fields(passports: &[Passport], group: &[usize]) -> Vec<String> {
+    let mut keys: Vec<&String> = group.iter().flat_map(|i| passports[*i].fields.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    return keys.into_iter()
+        .filter(|key| group.iter().map(|i| passports[*i].fields.get(*key)).any(|v| v != passports[group[0]].fields.get(*key)))
+        .cloned()
+        .collect();
+}
+
+fn print_duplicates(passports: &[Passport]) {
+    let (exact, near) = find_duplicates(passports);
+    let numbers = |group: &[usize]| group.iter().map(|i| (i + 1).to_string()).collect::<Vec<String>>().join(", ");
+    println!("Identical passports: {}", exact.len());
+    for group in &exact {
+        println!("  {}", numbers(group));
+    }
+    println!("Passports sharing a pid: {}", near.len());
+    for group in &near {
+        println!("  pid {}: {} (differ in {})", passports[group[0]].fields["pid"], numbers(group), differing_fields(passports, group).join(", "));
+    }
+}
+
+// option: --schema <file>, defaults to SCHEMA
+fn schema(args: &Args) -> Schema {
+    return Schema::from_file(args.value("schema").unwrap_or(SCHEMA));
+}
+
+pub fn part_one(args: &Args) {
     println!("--- Part One ---");
 
+    let schema = schema(args);
     let passports = Passport::from_file("./data/dec_04.txt");
     let result = passports.iter()
-        .filter(|p| p.fields_present())
+        .filter(|p| schema.check_fields(p).is_empty())
         .count();
     println!("Result: {}", result)
 }
 
-pub fn part_two() {
+// option: --passport-verbose lists the failures of every invalid passport
+pub fn part_two(args: &Args) {
     println!("--- Part Two ---");
 
+    let schema = schema(args);
     let passports = Passport::from_file("./data/dec_04.txt");
-    let result = passports.iter()
-        .filter(|p| p.fields_present())
-        .filter(|p| p.fields_valid())
-        .count();
+    let mut result = 0;
+    for (i, passport) in passports.iter().enumerate() {
+        let failures = schema.validate(passport);
+        if failures.is_empty() {
+            result += 1;
+        } else if args.flag("passport-verbose") {
+            println!("Passport {}: {}", i + 1, failures.iter().map(|f| f.to_string()).collect::<Vec<String>>().join("; "));
+        }
+    }
     println!("Result: {}", result)
 }
+
+// options: --export-jsonl <file>, --export-csv <file>, --duplicates
+pub fn export(args: &Args) {
+    println!("--- Export ---");
+
+    let schema = schema(args);
+    let passports = Passport::from_file("./data/dec_04.txt");
+    if let Some(filename) = args.value("export-jsonl") {
+        fs::write(filename, to_json_lines(&passports, &schema)).unwrap_or_else(|e| panic!("error writing {}: {}", filename, e));
+        println!("Wrote {} passports to {}", passports.len(), filename);
+    }
+    if let Some(filename) = args.value("export-csv") {
+        fs::write(filename, to_csv(&passports, &schema)).unwrap_or_else(|e| panic!("error writing {}: {}", filename, e));
+        println!("Wrote {} passports to {}", passports.len(), filename);
+    }
+    if args.flag("duplicates") {
+        print_duplicates(&passports);
+    }
+}
@@ -1,36 +1,112 @@
+use std::collections::HashSet;
+
+use crate::aabb::Aabb;
+use crate::cli::Args;
 use crate::common;
+use crate::vectors::Vec2;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Seat {
-    // row: i32,
-    // column: i32,
+    row: i32,
+    column: i32,
     id: i32,
 }
 
-impl Seat {
-    fn parse(string: &String) -> Seat {
-        let binary_string: String = string.chars()
-            .map(|c| match c {
-                'F' => '0',
-                'B' => '1',
-                'L' => '0',
-                'R' => '1',
-                _ => '0'
-            })
-            .collect();
-        let value = i32::from_str_radix(&binary_string, 2).unwrap();
-        return Seat {
-            // row: value >> 3,
-            // column: value & 7,
-            id: value,
+// A boarding pass has row_bits F (front) or B (back) characters followed by column_bits
+// L (left) or R (right) characters, which are binary numbers with the back and right as 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Geometry {
+    row_bits: u32,
+    column_bits: u32,
+}
+
+impl Geometry {
+    const DEFAULT: Geometry = Geometry { row_bits: 7, column_bits: 3 };
+
+    fn new(row_bits: u32, column_bits: u32) -> Result<Geometry, String> {
+        if row_bits + column_bits > 30 {
+            return Err(format!("{} row and {} column bits do not fit into a seat id", row_bits, column_bits));
+        }
+        Ok(Geometry { row_bits, column_bits })
+    }
+
+    fn rows(&self) -> i32 {
+        1 << self.row_bits
+    }
+
+    fn columns(&self) -> i32 {
+        1 << self.column_bits
+    }
+
+    fn seat(&self, row: i32, column: i32) -> Result<Seat, String> {
+        if row < 0 || row >= self.rows() || column < 0 || column >= self.columns() {
+            return Err(format!("row {}, column {} is outside of {} rows and {} columns", row, column, self.rows(), self.columns()));
+        }
+        Ok(Seat { row, column, id: row * self.columns() + column })
+    }
+
+    fn seat_from_id(&self, id: i32) -> Result<Seat, String> {
+        if id < 0 || id >= self.rows() * self.columns() {
+            return Err(format!("seat id {} is outside of {} rows and {} columns", id, self.rows(), self.columns()));
+        }
+        self.seat(id >> self.column_bits, id & (self.columns() - 1))
+    }
+
+    fn decode(&self, pass: &str) -> Result<Seat, String> {
+        let length = (self.row_bits + self.column_bits) as usize;
+        if pass.chars().count() != length {
+            return Err(format!("boarding pass {} should have {} characters", pass, length));
+        }
+        let mut id = 0;
+        for (i, c) in pass.chars().enumerate() {
+            let (zero, one) = if i < self.row_bits as usize { ('F', 'B') } else { ('L', 'R') };
+            let bit = match c {
+                c if c == zero => 0,
+                c if c == one => 1,
+                _ => return Err(format!("boarding pass {} has {} at position {}, expected {} or {}", pass, c, i + 1, zero, one)),
+            };
+            id = id << 1 | bit;
+        }
+        self.seat_from_id(id)
+    }
+
+    fn encode(&self, seat: &Seat) -> String {
+        let bits = |value: i32, count: u32, zero: char, one: char| -> String {
+            (0..count).rev().map(|i| if value >> i & 1 == 1 { one } else { zero }).collect()
         };
+        format!("{}{}", bits(seat.row, self.row_bits, 'F', 'B'), bits(seat.column, self.column_bits, 'L', 'R'))
+    }
+
+    // # for taken seats, . for free ones and O for the gaps between taken seats
+    fn render(&self, seats: &[Seat]) -> String {
+        let taken: HashSet<i32> = seats.iter().map(|s| s.id).collect();
+        let mut ids: Vec<i32> = taken.iter().copied().collect();
+        ids.sort();
+        let gaps: HashSet<i32> = find_missing_values_in_sequence(&ids).into_iter().collect();
+        let bounds = Aabb::new(Vec2::new(0, 0), Vec2::new(self.columns() - 1, self.rows() - 1));
+        bounds.render(|pos| {
+            let id = pos.y * self.columns() + pos.x;
+            if taken.contains(&id) {
+                '#'
+            } else if gaps.contains(&id) {
+                'O'
+            } else {
+                '.'
+            }
+        })
     }
 }
 
+fn read_seats(geometry: &Geometry) -> Vec<Seat> {
+    common::read_strings("./data/dec_05.txt").iter()
+        .map(|s| geometry.decode(s).unwrap_or_else(|e| panic!("error: {}", e)))
+        .collect()
+}
+
 pub fn part_one() {
     println!("--- Part One ---");
 
-    let result: i32 = common::read_strings("./data/dec_05.txt").iter()
-        .map(|s| Seat::parse(s))
+    let result: i32 = read_seats(&Geometry::DEFAULT).iter()
         .map(|p| p.id)
         .max()
         .unwrap_or(0);
@@ -40,22 +116,36 @@ pub fn part_one() {
 pub fn part_two() {
     println!("--- Part Two ---");
 
-    let mut ids: Vec<i32> = common::read_strings("./data/dec_05.txt").iter()
-        .map(|s| Seat::parse(s))
+    let mut ids: Vec<i32> = read_seats(&Geometry::DEFAULT).iter()
         .map(|s| s.id)
         .collect();
 
     ids.sort();
 
-    let result = find_missing_value_in_sequence(&ids);
-    println!("Result: {}", result);
+    let result = find_missing_values_in_sequence(&ids);
+    println!("Result: {}", result.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(", "));
 }
 
-fn find_missing_value_in_sequence(ids: &Vec<i32>) -> i32 {
-    for (i, id) in ids.iter().enumerate() {
-        if i + 1 < ids.len() && ids[i + 1] != id + 1 {
-            return id + 1;
-        }
+// every value between the first and the last that is not in the sorted ids
+fn find_missing_values_in_sequence(ids: &[i32]) -> Vec<i32> {
+    ids.windows(2)
+        .flat_map(|pair| pair[0] + 1..pair[1])
+        .collect()
+}
+
+// options: --row-bits <n> and --column-bits <n> for other aircraft, --seat-map prints all seats,
+// --encode <seat id> prints its boarding pass
+pub fn inspect(args: &Args) {
+    println!("--- Inspect ---");
+
+    let geometry = Geometry::new(args.get_or("row-bits", Geometry::DEFAULT.row_bits), args.get_or("column-bits", Geometry::DEFAULT.column_bits))
+        .unwrap_or_else(|e| panic!("error: {}", e));
+    if let Some(id) = args.value("encode") {
+        let id = id.parse::<i32>().unwrap_or_else(|e| panic!("error: {}", e));
+        let seat = geometry.seat_from_id(id).unwrap_or_else(|e| panic!("error: {}", e));
+        println!("Seat {} (row {}, column {}): {}", seat.id, seat.row, seat.column, geometry.encode(&seat));
+    }
+    if args.flag("seat-map") {
+        print!("{}", geometry.render(&read_seats(&geometry)));
     }
-    return -1;
 }
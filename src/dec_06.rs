@@ -1,14 +1,147 @@
-use std::collections::HashMap;
-
-use itertools::Itertools;
+use std::fmt;
 
+use crate::cli::Args;
 use crate::common;
 
+const QUESTIONS: usize = 26;
+
+// the questions one person answered with yes, bit i stands for question 'a' + i
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Answers(u32);
+
+impl Answers {
+    const ALL: Answers = Answers((1 << QUESTIONS) - 1);
+
+    fn parse(line: &str) -> Result<Answers, String> {
+        let mut bits = 0;
+        for c in line.chars() {
+            if !c.is_ascii_lowercase() {
+                return Err(format!("{} in \"{}\" is not a question", c, line));
+            }
+            bits |= 1 << (c as u32 - 'a' as u32);
+        }
+        Ok(Answers(bits))
+    }
+
+    fn count(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    fn contains(&self, question: usize) -> bool {
+        self.0 >> question & 1 == 1
+    }
+
+    // number of questions only one of the two answered
+    fn distance(&self, other: &Answers) -> usize {
+        (self.0 ^ other.0).count_ones() as usize
+    }
+
+    fn from_questions<I>(questions: I) -> Answers where I: IntoIterator<Item=usize> {
+        Answers(questions.into_iter().fold(0, |bits, q| bits | 1 << q))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let questions: String = (0..QUESTIONS)
+            .filter(|q| self.contains(*q))
+            .map(|q| (b'a' + q as u8) as char)
+            .collect();
+        write!(f, "{}", questions)
+    }
+}
+
+struct Group {
+    members: Vec<Answers>,
+}
+
+impl Group {
+    fn parse(chunk: &[String]) -> Group {
+        let members = chunk.iter()
+            .map(|line| Answers::parse(line).unwrap_or_else(|e| panic!("error: {}", e)))
+            .collect();
+        Group { members }
+    }
+
+    fn union(&self) -> Answers {
+        Answers(self.members.iter().fold(0, |bits, a| bits | a.0))
+    }
+
+    // empty for a group without members
+    fn intersection(&self) -> Answers {
+        if self.members.is_empty() {
+            return Answers::default();
+        }
+        Answers(self.members.iter().fold(Answers::ALL.0, |bits, a| bits & a.0))
+    }
+
+    // number of members that answered each question
+    fn counts(&self) -> [usize; QUESTIONS] {
+        let mut counts = [0; QUESTIONS];
+        for member in &self.members {
+            for (q, count) in counts.iter_mut().enumerate() {
+                if member.contains(q) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    // only questions somebody answered count, so k = 0 behaves like k = 1
+    fn answered_by_at_least(&self, k: usize) -> Answers {
+        Answers::from_questions(self.counts().iter().enumerate().filter(|(_, c)| **c > 0 && **c >= k).map(|(q, _)| q))
+    }
+
+    // only questions somebody answered count, so k = 0 gives none
+    fn answered_by_exactly(&self, k: usize) -> Answers {
+        Answers::from_questions(self.counts().iter().enumerate().filter(|(_, c)| **c > 0 && **c == k).map(|(q, _)| q))
+    }
+
+    // The member whose answers differ most from everybody else's, measured as the sum of the
+    // distances to all other members, and that sum. None for groups of less than two.
+    fn most_divergent_member(&self) -> Option<(usize, usize)> {
+        if self.members.len() < 2 {
+            return None;
+        }
+        self.members.iter()
+            .map(|m| self.members.iter().map(|other| m.distance(other)).sum::<usize>())
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, distance)| *distance)
+    }
+}
+
+// per question: members that answered it, groups where anybody did and groups where everybody did
+fn histogram(groups: &[Group]) -> [(usize, usize, usize); QUESTIONS] {
+    let mut histogram = [(0, 0, 0); QUESTIONS];
+    for group in groups {
+        let counts = group.counts();
+        for (q, row) in histogram.iter_mut().enumerate() {
+            row.0 += counts[q];
+            if counts[q] > 0 {
+                row.1 += 1;
+            }
+            if counts[q] == group.members.len() {
+                row.2 += 1;
+            }
+        }
+    }
+    histogram
+}
+
+fn read_groups() -> Vec<Group> {
+    common::read_chunks("./data/dec_06.txt").iter()
+        .filter(|chunk| !chunk.is_empty())
+        .map(|chunk| Group::parse(chunk))
+        .collect()
+}
+
 pub fn part_one() {
     println!("--- Part One ---");
 
-    let sum: usize = common::read_chunks("./data/dec_06.txt").iter()
-        .map(|c| c.iter().flat_map(|l| l.chars()).unique().count())
+    let sum: usize = read_groups().iter()
+        .map(|g| g.union().count())
         .sum();
     println!("Result: {}", sum);
 }
@@ -16,28 +149,44 @@ pub fn part_one() {
 pub fn part_two() {
     println!("--- Part Two ---");
 
-    let sum: usize = common::read_chunks("./data/dec_06.txt").iter()
-        .map(|chunk| (chunk_to_count_map(chunk), chunk.len() as i32))
-        .map(|(char_counts, len)| {
-            char_counts.iter()
-                .filter(|(_, &value)| value == len)
-                .count()
-        }).sum();
+    let sum: usize = read_groups().iter()
+        .map(|g| g.intersection().count())
+        .sum();
     println!("Result: {}", sum);
 }
 
-fn chunk_to_count_map(chunk: &Vec<String>) -> HashMap<char, i32> {
-    return to_count_map(&chars_from_chunk(chunk));
-}
+// options: --at-least <k> and --exactly <k> sum up the questions answered with yes by at least
+// or exactly k members of each group, k has to be at least 1, --histogram prints the answers
+// per question, --divergent <group> finds the member of the group (counted from 1) that
+// differs most from the others
+pub fn query(args: &Args) {
+    println!("--- Query ---");
 
-fn to_count_map(chars: &Vec<char>) -> HashMap<char, i32> {
-    let mut map: HashMap<char, i32> = HashMap::new();
-    for c in chars {
-        map.insert(*c, map.get(&c).unwrap_or(&0) + 1);
+    let groups = read_groups();
+    let quorum = |name: &str| match args.get::<usize>(name) {
+        Some(0) => panic!("error: --{} has to be at least 1, questions nobody answered are not counted", name),
+        k => k,
+    };
+    if let Some(k) = quorum("at-least") {
+        let sum: usize = groups.iter().map(|g| g.answered_by_at_least(k).count()).sum();
+        println!("Answered by at least {}: {}", k, sum);
+    }
+    if let Some(k) = quorum("exactly") {
+        let sum: usize = groups.iter().map(|g| g.answered_by_exactly(k).count()).sum();
+        println!("Answered by exactly {}: {}", k, sum);
+    }
+    if args.flag("histogram") {
+        println!("Question | Members | Any of group | All of group");
+        for (q, (members, any, all)) in histogram(&groups).iter().enumerate() {
+            println!("{:>8} | {:>7} | {:>12} | {:>12}", (b'a' + q as u8) as char, members, any, all);
+        }
+    }
+    if let Some(number) = args.get::<usize>("divergent") {
+        let group = groups.get(number.wrapping_sub(1)).unwrap_or_else(|| panic!("error: there are {} groups", groups.len()));
+        match group.most_divergent_member() {
+            Some((member, distance)) => println!("Group {}: member {} ({}) differs in {} answers from the others",
+                                                 number, member + 1, group.members[member], distance),
+            None => println!("Group {} has only one member", number),
+        }
     }
-    return map;
-}
-
-fn chars_from_chunk(chunk: &Vec<String>) -> Vec<char> {
-    return chunk.iter().flat_map(|l| l.chars()).collect::<Vec<char>>();
 }
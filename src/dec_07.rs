@@ -1,177 +1,214 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::fmt;
 
 use lazy_static::lazy_static;
+use num::bigint::BigInt;
+use num::{One, Zero};
 use regex::Regex;
 
+use crate::cli::Args;
 use crate::common;
-
-#[derive(Clone, Debug)]
-struct Bags {
-    map: HashMap<String, usize>,
-    list: Vec<Bag>,
+use crate::graph::{Digraph, Direction};
+
+// why a rule set was rejected, lines are counted from 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RuleError {
+    Syntax { line: usize, text: String },
+    SelfReference { line: usize, color: String },
+    DuplicateRule { color: String, first_line: usize, line: usize },
+    // the colors of the cycle, the first one is repeated at the end
+    Cycle(Vec<String>),
 }
 
-#[derive(Clone, Debug)]
-struct Bag {
-    index: usize,
-    color: String,
-    parents: Vec<BagWithQuantity>,
-    children: Vec<BagWithQuantity>,
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::Syntax { line, text } => write!(f, "line {}: cannot parse \"{}\"", line, text),
+            RuleError::SelfReference { line, color } => write!(f, "line {}: {} bags contain themselves", line, color),
+            RuleError::DuplicateRule { color, first_line, line } =>
+                write!(f, "line {}: {} bags are already defined on line {}", line, color, first_line),
+            RuleError::Cycle(colors) => write!(f, "bags contain themselves: {}", colors.join(" -> ")),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-struct BagWithQuantity {
-    bag_index: usize,
-    quantity: i32,
+// edges point from the outer bag to the bags it contains, weighted by quantity
+struct Bags {
+    graph: Digraph<u32>,
 }
 
 impl Bags {
-    fn parse(strings: Vec<String>) -> Bags {
+    fn parse(strings: Vec<String>) -> Result<Bags, RuleError> {
         lazy_static! {
-            static ref PRIMARY: Regex = Regex::new(r"^(.+)? bags contain (.+)s?\.$").unwrap();
-            static ref CHILD: Regex = Regex::new(r"^(\d) (.+)? bags?$").unwrap();
+            static ref PRIMARY: Regex = Regex::new(r"^(.+) bags contain (.+)s?\.$").unwrap();
+            static ref CHILD: Regex = Regex::new(r"^(\d+) (.+) bags?$").unwrap();
             static ref CHILD_ZERO: Regex = Regex::new(r"^no other bags$").unwrap();
         }
 
-        let mut map: HashMap<String, usize> = HashMap::new();
-        let mut list: Vec<Bag> = Vec::new();
+        let mut graph: Digraph<u32> = Digraph::new();
+        let mut defined_on: HashMap<String, usize> = HashMap::new();
 
-        for string in strings {
-            if !PRIMARY.is_match(&string) {
-                panic!("error: {}", string);
-            }
-            let caps = PRIMARY.captures(&string).unwrap();
+        for (i, string) in strings.iter().enumerate() {
+            let line = i + 1;
+            let caps = PRIMARY.captures(string).ok_or(RuleError::Syntax { line, text: string.clone() })?;
             let color = caps[1].to_string();
-            let rest = caps[2].to_string();
-            let child_strings = rest.split(",").map(|c| c.trim()).collect::<Vec<&str>>();
-
-            let result = Bags::insert(map, list, &color);
-            map = result.0;
-            list = result.1;
-            let parent = result.2;
-
-            // let parent = list[parent_index].clone();
-            let mut children: Vec<BagWithQuantity> = Vec::new();
-            children.extend(parent.children.to_vec());
+            if let Some(first_line) = defined_on.insert(color.clone(), line) {
+                return Err(RuleError::DuplicateRule { color, first_line, line });
+            }
+            graph.add_node(&color);
 
-            for child_string in child_strings {
+            for child_string in caps[2].split(',').map(|c| c.trim()) {
                 if CHILD_ZERO.is_match(child_string) {
                     continue;
                 }
-                if !CHILD.is_match(child_string) {
-                    panic!("error: {}", child_string);
+                let caps = CHILD.captures(child_string).ok_or(RuleError::Syntax { line, text: child_string.to_string() })?;
+                if caps[2] == color {
+                    return Err(RuleError::SelfReference { line, color });
                 }
-
-                let caps = CHILD.captures(child_string).unwrap();
-                let quantity = caps[1].to_string().parse::<i32>().unwrap();
-                let child_color = caps[2].to_string();
-
-                let result = Bags::insert(map, list, &child_color);
-                map = result.0;
-                list = result.1;
-                let child = result.2;
-
-                children.push(BagWithQuantity {
-                    quantity,
-                    bag_index: child.index,
-                });
-
-                // let child = &list[child_index];
-                let mut parents: Vec<BagWithQuantity> = Vec::new();
-                parents.extend(child.parents.to_vec());
-                parents.push(BagWithQuantity {
-                    quantity,
-                    bag_index: parent.index,
-                });
-
-                list[child.index] = Bag {
-                    index: child.index,
-                    color: child.color.clone(),
-                    parents,
-                    children: child.children.clone(),
-                };
+                let quantity = caps[1].parse::<u32>().map_err(|_| RuleError::Syntax { line, text: child_string.to_string() })?;
+                graph.add_edge(&color, &caps[2], quantity);
             }
-
-            list[parent.index] = Bag {
-                index: parent.index,
-                color: parent.color.clone(),
-                parents: parent.parents.clone(),
-                children,
-            };
         }
-        return Bags {
-            map,
-            list,
-        };
-    }
 
-    fn insert(mut map: HashMap<String, usize>, mut list: Vec<Bag>, color: &String) -> (HashMap<String, usize>, Vec<Bag>, Bag) {
-        let index: usize;
-        let bag: Bag;
-        if map.contains_key(color) {
-            index = *map.get(color).unwrap();
-        } else {
-            index = list.len();
-            list.push(Bag {
-                index,
-                color: color.clone(),
-                parents: vec![],
-                children: vec![],
-            });
-            map.insert(color.clone(), index);
+        if let Some(cycle) = graph.find_cycle() {
+            let mut colors: Vec<String> = graph.names(&cycle).iter().map(|c| c.to_string()).collect();
+            colors.push(colors[0].clone());
+            return Err(RuleError::Cycle(colors));
         }
-        bag = list[index].clone();
-
-        return (map, list, bag);
+        return Ok(Bags { graph });
     }
 
-    fn count_number_of_unique_parents_for_color(&self, color: &String) -> usize {
-        let start_index = self.map.get(color).unwrap();
-        let mut indices: HashSet<i32> = HashSet::new();
-        indices = self.count_number_of_unique_parents_for_index(*start_index, indices);
-        return indices.len();
+    fn from_file(filename: &str) -> Bags {
+        return Bags::parse(common::read_strings(filename)).unwrap_or_else(|e| panic!("error: {}", e));
     }
 
-    fn count_number_of_unique_parents_for_index(&self, index: usize, mut indices: HashSet<i32>) -> HashSet<i32> {
-        let bag: &Bag = self.list.get(index).unwrap();
-
-        for parent in &bag.parents {
-            indices.insert(parent.bag_index as i32);
-            indices = self.count_number_of_unique_parents_for_index(parent.bag_index, indices);
-        }
+    fn index_of(&self, color: &str) -> Result<usize, String> {
+        return self.graph.index_of(color).ok_or(format!("there is no rule for {} bags", color));
+    }
 
-        return indices;
+    // every color that eventually contains the given one, sorted
+    fn ancestors(&self, color: &str) -> Result<Vec<&str>, String> {
+        let index = self.index_of(color)?;
+        let mut ancestors = self.graph.names(&self.graph.reachable(index, Direction::Parents).into_iter().collect::<Vec<usize>>());
+        ancestors.sort_unstable();
+        return Ok(ancestors);
     }
 
-    fn count_number_of_cumulative_children_for_color(&self, color: &String) -> i32 {
-        let start_index = self.map.get(color).unwrap();
-        return self.count_number_of_cumulative_children_for_index(*start_index) - 1;
+    // number of bags inside the given one, every rule is evaluated once
+    fn total_contents(&self, color: &str) -> Result<BigInt, String> {
+        let index = self.index_of(color)?;
+        // parse rejects cycles
+        return Ok(self.graph.fold(index, |_, children| {
+            children.iter().map(|(quantity, count)| BigInt::from(**quantity) * (BigInt::one() + *count)).sum()
+        }).unwrap());
     }
 
-    fn count_number_of_cumulative_children_for_index(&self, index: usize) -> i32 {
-        let bag: &Bag = self.list.get(index).unwrap();
+    // Every way from outside down to inside, as the colors on the way and the quantity of each
+    // of them per bag of the previous one. The total number of inside bags per path is the
+    // product of the quantities.
+    fn containment_paths(&self, outside: &str, inside: &str) -> Result<Vec<Vec<(String, u32)>>, String> {
+        let from = self.index_of(outside)?;
+        let to = self.index_of(inside)?;
+        // a bag does not contain itself
+        if from == to {
+            return Ok(Vec::new());
+        }
+        // only colors that can lead to inside are worth visiting
+        let leads_to_inside = self.graph.reachable(to, Direction::Parents);
+        let mut paths = Vec::new();
+        let mut stack: Vec<(usize, Vec<(String, u32)>)> = vec![(from, vec![(outside.to_string(), 1)])];
+        while let Some((node, path)) = stack.pop() {
+            if node == to {
+                paths.push(path);
+                continue;
+            }
+            for edge in self.graph.children(node).iter().rev() {
+                if edge.node == to || leads_to_inside.contains(&edge.node) {
+                    let mut next = path.clone();
+                    next.push((self.graph.name(edge.node).to_string(), edge.weight));
+                    stack.push((edge.node, next));
+                }
+            }
+        }
+        return Ok(paths);
+    }
 
-        let mut total = 1;
-        for child in &bag.children {
-            total += child.quantity * self.count_number_of_cumulative_children_for_index(child.bag_index);
+    // the longest chain of bags directly inside each other
+    fn deepest_chain(&self) -> Vec<&str> {
+        // depth below every node and the child that reaches it
+        let mut memo: HashMap<usize, (usize, Option<usize>)> = HashMap::new();
+        let mut deepest: Option<(usize, usize)> = None;
+        for start in 0..self.graph.len() {
+            let graph = &self.graph;
+            let (depth, _) = graph.fold_memoized(start, &mut memo, &|node, children: &[(&u32, &(usize, Option<usize>))]| {
+                children.iter()
+                    .zip(graph.children(node))
+                    .map(|((_, (depth, _)), edge)| (depth + 1, Some(edge.node)))
+                    .max_by_key(|(depth, _)| *depth)
+                    .unwrap_or((0, None))
+            }).unwrap();
+            if deepest.is_none_or(|(d, _)| depth > d) {
+                deepest = Some((depth, start));
+            }
         }
 
-        return total;
+        let mut chain = Vec::new();
+        let mut next = deepest.map(|(_, start)| start);
+        while let Some(node) = next {
+            chain.push(node);
+            next = memo[&node].1;
+        }
+        return self.graph.names(&chain);
     }
 }
 
-pub fn part_one() {
+const BAG: &str = "shiny gold";
+
+// option: --bag <color> instead of shiny gold
+fn bag(args: &Args) -> &str {
+    return args.value("bag").unwrap_or(BAG);
+}
+
+pub fn part_one(args: &Args) {
     println!("--- Part One ---");
 
-    let bags: Bags = Bags::parse(common::read_strings("./data/dec_07.txt"));
-    let count = bags.count_number_of_unique_parents_for_color(&"shiny gold".to_string());
-    println!("Result: {}", count);
+    let bags: Bags = Bags::from_file("./data/dec_07.txt");
+    let ancestors = bags.ancestors(bag(args)).unwrap_or_else(|e| panic!("error: {}", e));
+    println!("Result: {}", ancestors.len());
 }
 
-pub fn part_two() {
+pub fn part_two(args: &Args) {
     println!("--- Part Two ---");
 
-    let bags: Bags = Bags::parse(common::read_strings("./data/dec_07.txt"));
-    let count = bags.count_number_of_cumulative_children_for_color(&"shiny gold".to_string());
+    let bags: Bags = Bags::from_file("./data/dec_07.txt");
+    let count = bags.total_contents(bag(args)).unwrap_or_else(|e| panic!("error: {}", e));
     println!("Result: {}", count);
 }
+
+// options: --ancestors lists the colors containing --bag, --paths-to <color> lists the ways that
+// color is nested in --bag, --deepest prints the longest chain of nested bags
+pub fn query(args: &Args) {
+    println!("--- Query ---");
+
+    let bags: Bags = Bags::from_file("./data/dec_07.txt");
+    let bag = bag(args);
+    if args.flag("ancestors") {
+        let ancestors = bags.ancestors(bag).unwrap_or_else(|e| panic!("error: {}", e));
+        println!("{} bags can be inside {} others: {}", bag, ancestors.len(), ancestors.join(", "));
+    }
+    if let Some(inside) = args.value("paths-to") {
+        let paths = bags.containment_paths(bag, inside).unwrap_or_else(|e| panic!("error: {}", e));
+        let mut total = BigInt::zero();
+        for path in &paths {
+            let count: BigInt = path.iter().map(|(_, quantity)| BigInt::from(*quantity)).product();
+            let steps: Vec<String> = path.iter().skip(1).map(|(color, quantity)| format!("{} {}", quantity, color)).collect();
+            println!("{} -> {} ({} bags)", bag, steps.join(" -> "), count);
+            total += count;
+        }
+        println!("{} paths, {} {} bags in total", paths.len(), total, inside);
+    }
+    if args.flag("deepest") {
+        let chain = bags.deepest_chain();
+        println!("{} bags deep: {}", chain.len(), chain.join(" -> "));
+    }
+}
@@ -1,9 +1,9 @@
 use std::collections::HashSet;
 
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::str::FromStr;
 
 use crate::common;
+use crate::pattern::line_pattern;
 
 #[derive(Clone, Debug)]
 enum Operation {
@@ -12,10 +12,26 @@ enum Operation {
     NOP,
 }
 
-#[derive(Clone, Debug)]
-struct Instruction {
-    operation: Operation,
-    argument: i32,
+impl FromStr for Operation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "acc" => Ok(Operation::ACC),
+            "jmp" => Ok(Operation::JMP),
+            "nop" => Ok(Operation::NOP),
+            _ => Err(format!("unknown operation {}", s)),
+        };
+    }
+}
+
+line_pattern! {
+    #[pattern("{} {}")]
+    #[derive(Clone, Debug)]
+    struct Instruction {
+        operation: Operation,
+        argument: i32,
+    }
 }
 
 pub fn part_one() {
@@ -66,26 +82,9 @@ pub fn part_two() {
 
 
 fn parse(filename: &str) -> Vec<Instruction> {
-    lazy_static! {
-        static ref LINE: Regex = Regex::new(r"^([a-z]{3}) ([+,\-]\d+)$").unwrap();
-    }
-
     return common::read_strings(filename).iter()
-        .map(|line| {
-            if LINE.is_match(&line) {
-                let caps = LINE.captures(&line).unwrap();
-                return Instruction {
-                    operation: match &caps[1] {
-                        "acc" => Operation::ACC,
-                        "jmp" => Operation::JMP,
-                        _ => Operation::NOP,
-                    },
-                    argument: caps[2].parse::<i32>().unwrap(),
-                };
-            } else {
-                panic!("error: {}", line);
-            }
-        }).collect();
+        .map(|line| line.parse::<Instruction>().unwrap_or_else(|e| panic!("error: {}", e)))
+        .collect();
 }
 
 fn run(instructions: &Vec<Instruction>) -> (bool, i32) {
@@ -23,7 +23,7 @@ pub fn part_two() {
         let slice = &numbers[first_index..last_index];
         let sum: i64 = slice.iter().sum();
         if sum == first_invalid_number {
-            println!("Result: {} ", slice.iter().min().unwrap() + slice.iter().max().unwrap());
+            println!("Result: {}", common::format_to_min_max_sum(slice));
             break;
         } else if sum < first_invalid_number {
             last_index += 1;
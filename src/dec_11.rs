@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter, Result};
 
+use crate::aabb::Aabb;
 use crate::common;
+use crate::neighborhood;
+use crate::ray;
 use crate::vectors::Vec2;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -55,39 +58,37 @@ impl Grid {
         };
     }
 
-    pub fn count_occupied(&self, index: usize, max_dist: i32) -> i32 {
+    pub fn bounds(&self) -> Aabb<i32, 2> {
+        return Aabb::new(Vec2::zero(), Vec2::new(self.width as i32 - 1, self.height as i32 - 1));
+    }
+
+    pub fn cell(&self, pos: Vec2<i32>) -> &CellState {
+        return &self.cells[pos.y as usize * self.width + pos.x as usize];
+    }
+
+    // max_dist None means looking until the end of the grid
+    pub fn count_occupied(&self, index: usize, max_dist: Option<u32>) -> i32 {
         let x = (index % self.width) as i32;
         let y = (index / self.width) as i32;
         let pos = Vec2::new(x, y);
 
-        return
-            self.get_occupied(pos.clone(), Vec2::new(-1, -1), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(0, -1), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(1, -1), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(-1, 0), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(1, 0), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(-1, 1), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(0, 1), max_dist)
-                + self.get_occupied(pos.clone(), Vec2::new(1, 1), max_dist);
+        return neighborhood::moore(Vec2::zero(), 1)
+            .map(|dir| self.get_occupied(pos, dir, max_dist))
+            .sum();
     }
 
-    pub fn get_occupied(&self, pos: Vec2<i32>, dir: Vec2<i32>, max_dist: i32) -> i32 {
-        let mut cur = pos.clone();
-        for _ in 0..max_dist {
-            cur = cur.clone() + dir.clone();
-            if cur.x < 0 || cur.x >= self.width as i32 || cur.y < 0 || cur.y >= self.height as i32 {
-                return 0;
-            }
-            match self.cells[cur.y as usize * self.width + cur.x as usize] {
-                CellState::OccupiedSeat => return 1,
-                CellState::EmptySeat => return 0,
-                _ => {}
-            }
+    pub fn get_occupied(&self, pos: Vec2<i32>, dir: Vec2<i32>, max_dist: Option<u32>) -> i32 {
+        let mut seats = ray::ray(pos, dir).within(self.bounds());
+        if let Some(max_dist) = max_dist {
+            seats = seats.max_steps(max_dist);
         }
-        return 0;
+        return match seats.cast(|p| *self.cell(p) != CellState::Floor) {
+            Some(hit) if *self.cell(hit.position) == CellState::OccupiedSeat => 1,
+            _ => 0,
+        };
     }
 
-    pub fn next(&self, min_occupied: i32, max_dist: i32) -> (Grid, bool) {
+    pub fn next(&self, min_occupied: i32, max_dist: Option<u32>) -> (Grid, bool) {
         let mut new_cells: Vec<CellState> = Vec::new();
         let mut changed = false;
         for (i, cell) in self.cells.iter().enumerate() {
@@ -121,7 +122,7 @@ pub fn part_one() {
 
     let mut grid = Grid::parse(common::read_strings("./data/dec_11.txt"));
     loop {
-        let (new_grid, changed) = grid.next(4, 1);
+        let (new_grid, changed) = grid.next(4, Some(1));
         if !changed {
             break;
         }
@@ -135,7 +136,7 @@ pub fn part_two() {
 
     let mut grid = Grid::parse(common::read_strings("./data/dec_11.txt"));
     loop {
-        let (new_grid, changed) = grid.next(5, 999);
+        let (new_grid, changed) = grid.next(5, None);
         if !changed {
             break;
         }
@@ -1,11 +1,14 @@
-use std::f32::consts::PI;
-
 use crate::common;
+use crate::matrices::Mat2;
 use crate::vectors::Vec2;
 
-pub fn from_rot(degrees: f32) -> Vec2<i32> {
-    let rad = degrees * PI / 180.0f32;
-    Vec2::new(rad.cos().round() as i32, rad.sin().round() as i32)
+// y points south, so positive angles turn right
+pub fn rotation(degrees: i32) -> Mat2<i32> {
+    return Mat2::rotation_degrees(degrees).unwrap_or_else(|| panic!("error: can only turn by multiples of 90 degrees, not {}", degrees));
+}
+
+pub fn from_rot(degrees: i32) -> Vec2<i32> {
+    return rotation(degrees) * Vec2::new(1, 0);
 }
 
 pub fn part_one() {
@@ -20,18 +23,18 @@ pub fn part_one() {
         let value = line[1..].parse::<i32>().unwrap();
 
         match action {
-            'N' => { pos = pos + Vec2::new(0, -value) }
-            'E' => { pos = pos + Vec2::new(value, 0) }
-            'S' => { pos = pos + Vec2::new(0, value) }
-            'W' => { pos = pos + Vec2::new(-value, 0) }
+            'N' => { pos += Vec2::new(0, -value) }
+            'E' => { pos += Vec2::new(value, 0) }
+            'S' => { pos += Vec2::new(0, value) }
+            'W' => { pos += Vec2::new(-value, 0) }
             'L' => { rot -= value }
             'R' => { rot += value }
-            'F' => { pos = pos + (from_rot(rot as f32) * value) }
+            'F' => { pos += from_rot(rot) * value }
             _ => {}
         }
     }
 
-    println!("Result: {:?}", pos.x.abs() + pos.y.abs());
+    println!("Result: {:?}", pos.dist_manhattan(&Vec2::zero()));
 }
 
 pub fn part_two() {
@@ -46,30 +49,16 @@ pub fn part_two() {
         let value = line[1..].parse::<i32>().unwrap();
 
         match action {
-            'N' => { way = way + Vec2::new(0, -value) }
-            'E' => { way = way + Vec2::new(value, 0) }
-            'S' => { way = way + Vec2::new(0, value) }
-            'W' => { way = way + Vec2::new(-value, 0) }
-            'L' => {
-                match value {
-                    90 => { way = Vec2::new(way.y, -way.x) }
-                    180 => { way = Vec2::new(-way.x, -way.y) }
-                    270 => { way = Vec2::new(-way.y, way.x) }
-                    _ => {}
-                }
-            }
-            'R' => {
-                match value {
-                    90 => { way = Vec2::new(-way.y, way.x) }
-                    180 => { way = Vec2::new(-way.x, -way.y) }
-                    270 => { way = Vec2::new(way.y, -way.x) }
-                    _ => {}
-                }
-            }
-            'F' => { pos = pos + (way.clone() * value) }
+            'N' => { way += Vec2::new(0, -value) }
+            'E' => { way += Vec2::new(value, 0) }
+            'S' => { way += Vec2::new(0, value) }
+            'W' => { way += Vec2::new(-value, 0) }
+            'L' => { way = rotation(-value) * way }
+            'R' => { way = rotation(value) * way }
+            'F' => { pos += way * value }
             _ => {}
         }
     }
 
-    println!("Result: {:?}", pos.x.abs() + pos.y.abs());
+    println!("Result: {:?}", pos.dist_manhattan(&Vec2::zero()));
 }
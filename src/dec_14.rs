@@ -1,9 +1,22 @@
 use std::collections::HashMap;
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::common;
+use crate::pattern::line_pattern;
+
+line_pattern! {
+    #[pattern("mask = {}")]
+    struct MaskLine {
+        mask: String,
+    }
+}
+
+line_pattern! {
+    #[pattern("mem[{}] = {}")]
+    struct MemLine {
+        address: u64,
+        value: u64,
+    }
+}
 
 #[derive(Clone, Debug)]
 struct Mask {
@@ -88,25 +101,16 @@ impl Mask {
 pub fn part_one() {
     println!("--- Part One ---");
 
-    lazy_static! {
-        static ref MASK: Regex = Regex::new(r"^mask = (.{36})$").unwrap();
-        static ref MEM: Regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
-    }
-
     let mut memory: HashMap<u64, u64> = HashMap::new();
     let mut mask: Mask = Mask::new();
 
     let lines = common::read_strings("./data/dec_14.txt");
 
     for line in lines {
-        if MASK.is_match(&line) {
-            let mask_string = MASK.captures(&line).unwrap()[1].to_string();
-            mask = Mask::parse(&mask_string);
-        } else if MEM.is_match(&line) {
-            let c = MEM.captures(&line).unwrap();
-            let mem_address = (&c[1]).parse::<u64>().unwrap();
-            let mem_value = (&c[2]).parse::<u64>().unwrap();
-            memory.insert(mem_address, mask.apply(mem_value));
+        if let Ok(mask_line) = line.parse::<MaskLine>() {
+            mask = Mask::parse(&mask_line.mask);
+        } else if let Ok(mem) = line.parse::<MemLine>() {
+            memory.insert(mem.address, mask.apply(mem.value));
         } else {
             panic!("error: {}", line);
         }
@@ -119,26 +123,17 @@ pub fn part_one() {
 pub fn part_two() {
     println!("--- Part Two ---");
 
-    lazy_static! {
-        static ref MASK: Regex = Regex::new(r"^mask = (.{36})$").unwrap();
-        static ref MEM: Regex = Regex::new(r"^mem\[(\d+)\] = (\d+)$").unwrap();
-    }
-
     let mut memory: HashMap<u64, u64> = HashMap::new();
     let mut masks: Vec<Mask> = vec![];
 
     let lines = common::read_strings("./data/dec_14.txt");
 
     for line in lines {
-        if MASK.is_match(&line) {
-            let mask_string = MASK.captures(&line).unwrap()[1].to_string();
-            masks = Mask::parse_2(&mask_string);
-        } else if MEM.is_match(&line) {
-            let c = MEM.captures(&line).unwrap();
-            let mem_address = (&c[1]).parse::<u64>().unwrap();
-            let mem_value = (&c[2]).parse::<u64>().unwrap();
+        if let Ok(mask_line) = line.parse::<MaskLine>() {
+            masks = Mask::parse_2(&mask_line.mask);
+        } else if let Ok(mem) = line.parse::<MemLine>() {
             for mask in &masks {
-                memory.insert(mask.apply(mem_address), mem_value);
+                memory.insert(mask.apply(mem.address), mem.value);
             }
         } else {
             panic!("error: {}", line);
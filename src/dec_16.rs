@@ -1,11 +1,21 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::BTreeMap;
 use std::ops::RangeInclusive;
 
-use lazy_static::lazy_static;
-use regex::Regex;
-
+use crate::assignment::{AssignmentSolver, Solution};
 use crate::common;
 use crate::common::format_to_product;
+use crate::pattern::line_pattern;
+
+line_pattern! {
+    #[pattern("{}: {}-{} or {}-{}")]
+    struct FieldLine {
+        name: String,
+        min_1: u32,
+        max_1: u32,
+        min_2: u32,
+        max_2: u32,
+    }
+}
 
 #[derive(Clone, Debug)]
 struct Field {
@@ -15,20 +25,15 @@ struct Field {
 }
 
 impl Field {
-    fn parse(line: &String) -> Field {
-        lazy_static! {
-            static ref LINE: Regex = Regex::new(r"^(.+)?: (\d+)-(\d+) or (\d+)-(\d+)$").unwrap();
-        }
-        if LINE.is_match(&line) {
-            let caps = LINE.captures(&line).unwrap();
-            return Field {
-                name: caps[1].to_string(),
-                range_1: RangeInclusive::new(caps[2].parse::<u32>().unwrap(), caps[3].parse::<u32>().unwrap()),
-                range_2: RangeInclusive::new(caps[4].parse::<u32>().unwrap(), caps[5].parse::<u32>().unwrap()),
-            };
-        } else {
-            panic!("error: {}", line);
-        }
+    fn parse(line: &str) -> Field {
+        return match line.parse::<FieldLine>() {
+            Ok(f) => Field {
+                name: f.name,
+                range_1: RangeInclusive::new(f.min_1, f.max_1),
+                range_2: RangeInclusive::new(f.min_2, f.max_2),
+            },
+            Err(e) => panic!("error: {}", e),
+        };
     }
 
     fn is_valid(&self, number: &u32) -> bool {
@@ -89,51 +94,26 @@ impl Data {
         };
     }
 
-    fn determine_all(&self) -> HashMap<usize, usize> {
-        let mut field_indices: HashSet<usize> = HashSet::new();
-        let mut ticket_field_indices: HashSet<usize> = HashSet::new();
-        let mut map: HashMap<usize, usize> = HashMap::new();
-
-        for i in 0..self.fields.len() {
-            field_indices.insert(i);
-            ticket_field_indices.insert(i);
+    fn determine_all(&self) -> BTreeMap<usize, usize> {
+        let mut solver = AssignmentSolver::new();
+        for ticket_field_index in 0..self.fields.len() {
+            solver.add(ticket_field_index, self.possible_fields(ticket_field_index));
         }
-
-        while field_indices.len() > 0 && ticket_field_indices.len() > 0 {
-            let mut ticket_field_indices_to_remove = Vec::new();
-            for ticket_field_index in &ticket_field_indices {
-                let field_index = self.determine_single_field(&field_indices, *ticket_field_index);
-                if field_index.is_some() {
-                    map.insert(*ticket_field_index, field_index.unwrap());
-                    ticket_field_indices_to_remove.push(ticket_field_index.clone());
-                    field_indices.remove(&field_index.unwrap());
-                }
-            }
-            for to_remove in ticket_field_indices_to_remove {
-                ticket_field_indices.remove(&to_remove);
-            }
-        }
-        return map;
+        return match solver.solve() {
+            Solution::Unique(map) => map,
+            Solution::Ambiguous(alternatives) => panic!("error: {} possible field orders, e.g. {:?}", alternatives.len(), alternatives[0]),
+            Solution::Unsatisfiable => panic!("error: no field order matches the nearby tickets"),
+        };
     }
 
-    fn determine_single_field(&self, field_indices: &HashSet<usize>, ticket_field_index: usize) -> Option<usize> {
+    fn possible_fields(&self, ticket_field_index: usize) -> Vec<usize> {
         let numbers: Vec<u32> = self.valid_nearby_tickets.iter()
             .map(|t| t.numbers[ticket_field_index])
             .collect();
 
-        let mut indices: Vec<usize> = Vec::new();
-        for i in field_indices {
-            let field = self.fields.get(*i).unwrap();
-            let matches = numbers.iter()
-                .all(|n| field.is_valid(n));
-            if matches {
-                indices.push(*i);
-            }
-        }
-        if indices.len() == 1 {
-            return Some(indices[0]);
-        }
-        return None;
+        return (0..self.fields.len())
+            .filter(|i| numbers.iter().all(|n| self.fields[*i].is_valid(n)))
+            .collect();
     }
 }
 
@@ -154,10 +134,10 @@ pub fn part_two() {
 
     let data = Data::parse("./data/dec_16.txt");
 
-    let map: HashMap<usize, usize> = data.determine_all();
-    let p: Vec<i32> = data.my_ticket.numbers.iter().enumerate()
+    let map: BTreeMap<usize, usize> = data.determine_all();
+    let p: Vec<u32> = data.my_ticket.numbers.iter().enumerate()
         .filter(|(i, _)| (&data.fields[map[&i]]).name.starts_with("departure"))
-        .map(|(_, n)| *n as i32)
+        .map(|(_, n)| *n)
         .collect();
     println!("Result: {}", format_to_product(&p));
 }
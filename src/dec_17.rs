@@ -1,108 +1,70 @@
-use std::collections::HashSet;
-
-use itertools::Itertools;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
 
+use crate::automaton::{Cell, SparseAutomaton};
 use crate::common;
+use crate::hashing::FastBuildHasher;
+use crate::neighborhood;
+use crate::packed::Packed64;
+use crate::vectors::VecN;
 
-#[derive(Clone, Debug)]
-struct Grid {
-    dimensions: usize,
-    permutations: HashSet<Vec<i32>>,
-    cells: HashSet<Vec<i32>>,
+// C is the D-dimensional cell type, either VecN itself or a packed key
+struct Grid<C, S = FastBuildHasher> where C: Cell, S: BuildHasher + Default {
+    automaton: SparseAutomaton<C, S>,
 }
 
-impl Grid {
-    fn new(dimensions: usize) -> Self {
-        let zero = vec![0i32; dimensions];
-        let permutations = vec![-1, 0, 1].into_iter()
-            .combinations_with_replacement(dimensions)
-            .flat_map(|co| co.into_iter()
-                .permutations(dimensions)
-                .unique())
-            .filter(|permutation| permutation != &zero)
-            .collect();
-
+impl<C, S> Grid<C, S> where C: Cell, S: BuildHasher + Default {
+    fn new<const D: usize>() -> Self where C: TryFrom<VecN<i32, D>>, C::Error: Debug {
         Self {
-            dimensions,
-            permutations,
-            cells: HashSet::new(),
+            automaton: SparseAutomaton::new(neighborhood::moore(VecN::zero(), 1).map(Self::cell).collect()),
         }
     }
 
-    fn parse(filename: &str, dimensions: usize) -> Self {
+    fn cell<const D: usize>(coords: VecN<i32, D>) -> C where C: TryFrom<VecN<i32, D>>, C::Error: Debug {
+        C::try_from(coords).unwrap()
+    }
+
+    fn parse<const D: usize>(filename: &str) -> Self where C: TryFrom<VecN<i32, D>>, C::Error: Debug {
         let lines = common::read_strings(filename);
-        let mut grid = Grid::new(dimensions);
+        let mut grid = Grid::new::<D>();
         for (y, line) in lines.iter().enumerate() {
             for (x, c) in line.chars().enumerate() {
                 if c == '#' {
-                    let mut coords = vec![0i32; dimensions];
+                    let mut coords = VecN::<i32, D>::zero();
                     coords[0] = x as i32;
                     coords[1] = y as i32;
-                    grid.cells.insert(coords);
+                    grid.automaton.insert(Self::cell(coords));
                 }
             }
         }
         grid
     }
 
-    fn is_set(&self, coords: &[i32]) -> bool {
-        self.cells.contains(coords)
-    }
-
-    fn count_neighbors(&self, coords: &[i32]) -> usize {
-        self.neighbors(&coords).into_iter().filter(|n| self.is_set(n)).count()
-    }
-
-    fn neighbors(&self, tile: &[i32]) -> Vec<Vec<i32>> {
-        self.permutations.iter()
-            .map(|permutation| Grid::add(tile, permutation))
-            .collect()
-    }
-
-    fn add(a: &[i32], b: &[i32]) -> Vec<i32> {
-        let mut z: Vec<i32> = vec![0i32; a.len()];
-        for ((zval, aval), bval) in z.iter_mut().zip(a).zip(b) {
-            *zval = aval + bval;
-        }
-        z
-    }
-
     fn count_all(&self) -> usize {
-        self.cells.len()
+        self.automaton.population()
     }
 
     fn iterate(&mut self) {
-        self.cells = self.get_affected_tiles().into_iter()
-            .filter(|tile| {
-                let count = self.count_neighbors(&tile);
-                self.is_set(&tile) && count == 2 || count == 3
-            }).collect();
+        self.automaton.step(|active, count| active && count == 2 || count == 3);
     }
+}
 
-    fn get_affected_tiles(&self) -> HashSet<Vec<i32>> {
-        let mut result = HashSet::new();
-        result.extend(self.cells.clone());
-        for tile in &self.cells {
-            result.extend(self.neighbors(tile));
-        }
-        result
+// number of active cubes after six cycles
+pub fn simulate<C, S, const D: usize>(filename: &str) -> usize where C: Cell + TryFrom<VecN<i32, D>>, C::Error: Debug, S: BuildHasher + Default {
+    let mut grid = Grid::<C, S>::parse::<D>(filename);
+    for _ in 0..6 {
+        grid.iterate();
     }
+    grid.count_all()
 }
 
 pub fn part_one() {
     println!("--- Part One ---");
-    let mut grid = Grid::parse("./data/dec_17.txt", 3);
-    for _ in 0..6 {
-        grid.iterate();
-    }
-    println!("Result: {}", grid.count_all());
+    println!("Result: {}", simulate::<Packed64<3>, FastBuildHasher, 3>("./data/dec_17.txt"));
 }
 
 pub fn part_two() {
     println!("--- Part Two ---");
-    let mut grid = Grid::parse("./data/dec_17.txt", 4);
-    for _ in 0..6 {
-        grid.iterate();
-    }
-    println!("Result: {}", grid.count_all());
+    println!("Result: {}", simulate::<Packed64<4>, FastBuildHasher, 4>("./data/dec_17.txt"));
 }
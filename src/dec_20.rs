@@ -4,6 +4,7 @@ use num::integer::Roots;
 
 use crate::common;
 use crate::common::Re;
+use crate::matrices::D4;
 
 #[derive(Debug, Clone)]
 struct Tile {
@@ -39,17 +40,9 @@ impl Tile {
     }
 
     pub fn get_variants(&self) -> Vec<Tile> {
-        let mirror = self.data.clone().slice_move(s![..,..;-1]);
-        return vec![
-            Tile { id: self.id, data: self.data.clone() },
-            Tile { id: self.id, data: self.data.clone().t().to_owned().slice_move(s![..,..;-1]) }, // rot 90
-            Tile { id: self.id, data: self.data.clone().slice_move(s![..;-1,..;-1]) }, // rot 180
-            Tile { id: self.id, data: self.data.clone().t().to_owned().slice_move(s![..;-1,..]) }, // rot 270
-            Tile { id: self.id, data: mirror.clone() },
-            Tile { id: self.id, data: mirror.t().to_owned().slice_move(s![..,..;-1]) }, // rot 90
-            Tile { id: self.id, data: mirror.clone().slice_move(s![..;-1,..;-1]) }, // rot 180
-            Tile { id: self.id, data: mirror.t().to_owned().slice_move(s![..;-1,..]) } // rot 270
-        ];
+        return D4::all().iter()
+            .map(|symmetry| Tile { id: self.id, data: symmetry.apply_to_array(&self.data) })
+            .collect();
     }
 
     pub fn top(&self) -> ArrayView<bool, Ix1> {
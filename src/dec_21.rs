@@ -1,8 +1,9 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashSet};
 use std::iter::FromIterator;
 
 use itertools::Itertools;
 
+use crate::assignment::{AssignmentSolver, Solution};
 use crate::common;
 
 #[derive(Debug, Clone)]
@@ -34,19 +35,18 @@ impl Food {
         }
         result
     }
-    pub fn remove(&mut self, allergen: &str, ingredient: &str) {
-        self.allergens.remove(allergen);
-        self.ingredients.remove(ingredient);
-    }
 }
 
 pub fn part_one() {
     println!("--- Part One ---");
 
     let foods = Food::parse("./data/dec_21.txt");
-    let (remaining_foods, _) = get_data(&foods);
+    let allergen_to_ingredient_map = get_data(&foods);
+    let dangerous: HashSet<&String> = allergen_to_ingredient_map.values().collect();
 
-    let result: usize = remaining_foods.iter().map(|f| f.ingredients.len()).sum();
+    let result: usize = foods.iter()
+        .map(|f| f.ingredients.iter().filter(|i| !dangerous.contains(i)).count())
+        .sum();
     println!("Result: {:?}", result);
 }
 
@@ -54,49 +54,23 @@ pub fn part_two() {
     println!("--- Part Two ---");
 
     let foods = Food::parse("./data/dec_21.txt");
-    let (_, allergen_to_food_map) = get_data(&foods);
+    let allergen_to_ingredient_map = get_data(&foods);
 
-    let mut dangerous_ingredients: Vec<_> = allergen_to_food_map.into_iter().collect();
-    dangerous_ingredients.sort_by(|x, y| x.0.cmp(&y.0));
-    let result = dangerous_ingredients.iter().map(|(_, v)| v).join(",");
+    // the map is ordered by allergen already
+    let result = allergen_to_ingredient_map.values().join(",");
     println!("Result: {}", result);
 }
 
-fn get_data(original_foods: &[Food]) -> (Vec<Food>, HashMap<String, String>) {
-    let mut foods = original_foods.to_vec();
-    let mut map = HashMap::new();
-    while let Some((allergen, ingredient)) = do_iteration(&foods) {
-        for food in &mut foods {
-            food.remove(&allergen, &ingredient);
-        }
-        map.insert(allergen, ingredient);
-    }
-    (foods, map)
-}
-
-fn do_iteration(foods: &[Food]) -> Option<(String, String)> {
-    let unique_allergens = HashSet::<String>::from_iter(foods.iter().flat_map(|f| f.allergens.clone().into_iter()));
-    let unique_ingredients = HashSet::<String>::from_iter(foods.iter().flat_map(|f| f.ingredients.clone().into_iter()));
-    for allergen in unique_allergens {
-        let mut food_with_allergen: Vec<Food> = vec![];
-        for food in foods {
-            if food.allergens.contains(&allergen) {
-                food_with_allergen.push(food.clone());
-            }
-        }
-        if let Some(correlation) = find_correlation(&food_with_allergen, &unique_ingredients) {
-            return Some((allergen, correlation));
+fn get_data(foods: &[Food]) -> BTreeMap<String, String> {
+    let mut solver = AssignmentSolver::new();
+    for food in foods {
+        for allergen in &food.allergens {
+            solver.add(allergen.clone(), food.ingredients.iter().cloned());
         }
     }
-    None
-}
-
-fn find_correlation(food_with_allergen: &[Food], unique_ingredients: &HashSet<String>) -> Option<String> {
-    let matches: Vec<&String> = unique_ingredients.iter().filter(|ingredient| {
-        food_with_allergen.iter().all(|food| food.ingredients.contains(*ingredient))
-    }).collect();
-    if matches.len() == 1 {
-        return Some(matches[0].clone());
+    match solver.solve() {
+        Solution::Unique(map) => map,
+        Solution::Ambiguous(alternatives) => panic!("error: {} possible allergen assignments, e.g. {:?}", alternatives.len(), alternatives[0]),
+        Solution::Unsatisfiable => panic!("error: allergens can not be assigned to ingredients"),
     }
-    None
 }
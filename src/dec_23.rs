@@ -58,7 +58,7 @@ pub fn part_two() {
     let r1 = cups[0].next;
     let r2 = cups[r1].next;
 
-    println!("Result: {}", common::format_to_product(&[r1 as i32 + 1, r2 as i32 + 1]));
+    println!("Result: {}", common::format_to_product(&[r1 + 1, r2 + 1]));
 }
 
 fn run(arr: &[usize], iterations: usize) -> Vec<Element> {
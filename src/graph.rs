@@ -0,0 +1,283 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Children,
+    Parents,
+}
+
+#[derive(Clone, Debug)]
+pub struct Edge<W> {
+    pub node: usize,
+    pub weight: W,
+}
+
+// directed graph with string keyed nodes and weighted edges
+#[derive(Clone, Debug)]
+pub struct Digraph<W> {
+    index: HashMap<String, usize>,
+    names: Vec<String>,
+    children: Vec<Vec<Edge<W>>>,
+    parents: Vec<Vec<Edge<W>>>,
+}
+
+impl<W> Default for Digraph<W> {
+    fn default() -> Self {
+        Digraph::new()
+    }
+}
+
+impl<W> Digraph<W> {
+    pub fn new() -> Self {
+        Digraph {
+            index: HashMap::new(),
+            names: Vec::new(),
+            children: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+
+    // returns the index of the node, inserting it if it does not exist yet
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(index) = self.index.get(name) {
+            return *index;
+        }
+        let index = self.names.len();
+        self.index.insert(name.to_string(), index);
+        self.names.push(name.to_string());
+        self.children.push(Vec::new());
+        self.parents.push(Vec::new());
+        index
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: W) where W: Clone {
+        let from_index = self.add_node(from);
+        let to_index = self.add_node(to);
+        self.children[from_index].push(Edge { node: to_index, weight: weight.clone() });
+        self.parents[to_index].push(Edge { node: from_index, weight });
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        &self.names[index]
+    }
+
+    pub fn names(&self, indices: &[usize]) -> Vec<&str> {
+        indices.iter().map(|i| self.name(*i)).collect()
+    }
+
+    pub fn children(&self, index: usize) -> &[Edge<W>] {
+        &self.children[index]
+    }
+
+    pub fn parents(&self, index: usize) -> &[Edge<W>] {
+        &self.parents[index]
+    }
+
+    pub fn edges(&self, index: usize, direction: Direction) -> &[Edge<W>] {
+        match direction {
+            Direction::Children => self.children(index),
+            Direction::Parents => self.parents(index),
+        }
+    }
+
+    // nodes in breadth first order, starting with the start node itself
+    pub fn bfs(&self, start: usize, direction: Direction) -> Vec<usize> {
+        let mut visited = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        visited[start] = true;
+        queue.push_back(start);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for edge in self.edges(node, direction) {
+                if !visited[edge.node] {
+                    visited[edge.node] = true;
+                    queue.push_back(edge.node);
+                }
+            }
+        }
+        order
+    }
+
+    // nodes in depth first preorder, starting with the start node itself
+    pub fn dfs(&self, start: usize, direction: Direction) -> Vec<usize> {
+        let mut visited = vec![false; self.len()];
+        let mut order = Vec::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            if visited[node] {
+                continue;
+            }
+            visited[node] = true;
+            order.push(node);
+            for edge in self.edges(node, direction).iter().rev() {
+                if !visited[edge.node] {
+                    stack.push(edge.node);
+                }
+            }
+        }
+        order
+    }
+
+    // all nodes reachable from start, excluding start unless it lies on a cycle
+    pub fn reachable(&self, start: usize, direction: Direction) -> HashSet<usize> {
+        let mut result = HashSet::new();
+        let mut stack: Vec<usize> = self.edges(start, direction).iter().map(|e| e.node).collect();
+        while let Some(node) = stack.pop() {
+            if result.insert(node) {
+                stack.extend(self.edges(node, direction).iter().map(|e| e.node));
+            }
+        }
+        result
+    }
+
+    // Kahn's algorithm; on failure the nodes of one cycle are returned instead
+    pub fn topological_order(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut in_degree: Vec<usize> = self.parents.iter().map(|p| p.len()).collect();
+        let mut queue: VecDeque<usize> = (0..self.len()).filter(|i| in_degree[*i] == 0).collect();
+        let mut order = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for edge in self.children(node) {
+                in_degree[edge.node] -= 1;
+                if in_degree[edge.node] == 0 {
+                    queue.push_back(edge.node);
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle().unwrap_or_default())
+        }
+    }
+
+    // returns the nodes of a cycle in edge order, e.g. [a, b, c] for a -> b -> c -> a
+    pub fn find_cycle(&self) -> Option<Vec<usize>> {
+        // nodes finished by an earlier start have no cycle below them and are skipped
+        let mut state = vec![0u8; self.len()];
+        (0..self.len()).find_map(|start| self.find_cycle_with(start, &mut state))
+    }
+
+    pub fn find_cycle_from(&self, start: usize) -> Option<Vec<usize>> {
+        self.find_cycle_with(start, &mut vec![0u8; self.len()])
+    }
+
+    // depth-first search from start, state is 0 = unvisited, 1 = on the current path, 2 = done
+    fn find_cycle_with(&self, start: usize, state: &mut [u8]) -> Option<Vec<usize>> {
+        if state[start] == 2 {
+            return None;
+        }
+        let mut path: Vec<usize> = vec![start];
+        let mut next_edge: Vec<usize> = vec![0];
+        state[start] = 1;
+        while let Some(node) = path.last().copied() {
+            let edge_index = next_edge.last_mut().unwrap();
+            if let Some(edge) = self.children[node].get(*edge_index) {
+                *edge_index += 1;
+                match state[edge.node] {
+                    0 => {
+                        state[edge.node] = 1;
+                        path.push(edge.node);
+                        next_edge.push(0);
+                    }
+                    1 => {
+                        let begin = path.iter().position(|n| *n == edge.node).unwrap();
+                        return Some(path[begin..].to_vec());
+                    }
+                    _ => {}
+                }
+            } else {
+                state[node] = 2;
+                path.pop();
+                next_edge.pop();
+            }
+        }
+        None
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    // Folds the sub-DAG below start bottom-up. Every node is evaluated once; f gets the node
+    // and the already folded values of its children together with the edge weights.
+    pub fn fold<T, F>(&self, start: usize, f: F) -> Result<T, Vec<usize>>
+        where T: Clone, F: Fn(usize, &[(&W, &T)]) -> T {
+        let mut memo: HashMap<usize, T> = HashMap::new();
+        self.fold_memoized(start, &mut memo, &f)
+    }
+
+    // like fold, but keeps the memo table so several queries can share it
+    pub fn fold_memoized<T, F>(&self, start: usize, memo: &mut HashMap<usize, T>, f: &F) -> Result<T, Vec<usize>>
+        where T: Clone, F: Fn(usize, &[(&W, &T)]) -> T {
+        let mut on_path = vec![false; self.len()];
+        let mut stack: Vec<(usize, bool)> = vec![(start, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if memo.contains_key(&node) {
+                continue;
+            }
+            if expanded {
+                let folded = {
+                    let inputs: Vec<(&W, &T)> = self.children[node].iter().map(|e| (&e.weight, &memo[&e.node])).collect();
+                    f(node, &inputs)
+                };
+                memo.insert(node, folded);
+                on_path[node] = false;
+                continue;
+            }
+            if on_path[node] {
+                return Err(self.find_cycle_from(node).unwrap_or_else(|| vec![node]));
+            }
+            on_path[node] = true;
+            stack.push((node, true));
+            for edge in &self.children[node] {
+                if !memo.contains_key(&edge.node) {
+                    if on_path[edge.node] {
+                        return Err(self.find_cycle_from(edge.node).unwrap_or_else(|| vec![edge.node]));
+                    }
+                    stack.push((edge.node, false));
+                }
+            }
+        }
+        Ok(memo[&start].clone())
+    }
+
+    // Sum over all descendants weighted by the product of the edge weights on the way there.
+    // Ok(None) if the count does not fit into a u64, Err with a cycle as for fold.
+    pub fn weighted_descendant_count(&self, start: usize) -> Result<Option<u64>, Vec<usize>> where W: Copy + Into<u64> {
+        self.fold(start, |_, children: &[(&W, &Option<u64>)]| {
+            children.iter().try_fold(0u64, |sum, (w, count)| {
+                let below = count.as_ref()?.checked_add(1)?;
+                sum.checked_add((**w).into().checked_mul(below)?)
+            })
+        })
+    }
+
+    pub fn to_dot(&self, graph_name: &str) -> String where W: Display {
+        let mut dot = format!("digraph \"{}\" {{\n", graph_name);
+        for (index, name) in self.names.iter().enumerate() {
+            dot.push_str(&format!("    n{} [label=\"{}\"];\n", index, name.replace('"', "\\\"")));
+        }
+        for (from, edges) in self.children.iter().enumerate() {
+            for edge in edges {
+                dot.push_str(&format!("    n{} -> n{} [label=\"{}\"];\n", from, edge.node, edge.weight));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,409 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::automaton::Cell;
+use crate::vectors::Vec3;
+
+// Cube ----------------------------------------------------------
+// The three coordinates always sum up to zero. For pointy-top hexes with y pointing down
+// e is (1, -1, 0), which matches the convention used in dec_24.
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cube {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Add for Cube {
+    type Output = Cube;
+    fn add(self, other: Self) -> Self::Output {
+        Cube::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Cube {
+    type Output = Cube;
+    fn sub(self, other: Self) -> Self::Output {
+        Cube::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Neg for Cube {
+    type Output = Cube;
+    fn neg(self) -> Self::Output {
+        Cube::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i32> for Cube {
+    type Output = Cube;
+    fn mul(self, other: i32) -> Self::Output {
+        Cube::new(self.x * other, self.y * other, self.z * other)
+    }
+}
+
+impl From<Cube> for Vec3<i32> {
+    fn from(c: Cube) -> Self {
+        Vec3::new(c.x, c.y, c.z)
+    }
+}
+
+impl TryFrom<Vec3<i32>> for Cube {
+    type Error = String;
+
+    fn try_from(v: Vec3<i32>) -> Result<Self, Self::Error> {
+        Cube::from_vec3(v).ok_or(format!("{} is not a cube coordinate, the components must sum to 0", v))
+    }
+}
+
+impl Cell for Cube {
+    fn offset(&self, by: &Self) -> Self {
+        *self + *by
+    }
+}
+
+impl Cube {
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Cube { x, y, z }
+    }
+
+    pub fn zero() -> Self {
+        Cube::default()
+    }
+
+    pub fn from_vec3(v: Vec3<i32>) -> Option<Self> {
+        if v.x + v.y + v.z == 0 {
+            Some(Cube::new(v.x, v.y, v.z))
+        } else {
+            None
+        }
+    }
+
+    pub fn neighbor(&self, direction: Direction) -> Cube {
+        *self + direction.offset()
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item=Cube> + '_ {
+        Direction::ALL.iter().map(move |d| self.neighbor(*d))
+    }
+
+    pub fn length(&self) -> i32 {
+        (self.x.abs() + self.y.abs() + self.z.abs()) / 2
+    }
+
+    pub fn distance(&self, other: &Cube) -> i32 {
+        (*self - *other).length()
+    }
+
+    // 60 degree steps around the origin, clockwise on screen (e becomes se)
+    pub fn rotate(&self, steps: i32) -> Cube {
+        let mut c = *self;
+        for _ in 0..steps.rem_euclid(6) {
+            c = Cube::new(-c.z, -c.x, -c.y);
+        }
+        c
+    }
+
+    pub fn rotate_around(&self, center: &Cube, steps: i32) -> Cube {
+        (*self - *center).rotate(steps) + *center
+    }
+
+    // all hexes at exactly radius steps, starting at the west corner and going clockwise
+    pub fn ring(&self, radius: i32) -> Vec<Cube> {
+        if radius <= 0 {
+            return vec![*self];
+        }
+        let mut result = Vec::with_capacity(6 * radius as usize);
+        let mut current = *self + Direction::W.offset() * radius;
+        for i in 0..6 {
+            let direction = Direction::ALL[(i + 5) % 6];
+            for _ in 0..radius {
+                result.push(current);
+                current = current.neighbor(direction);
+            }
+        }
+        result
+    }
+
+    // the center followed by the rings 1 to radius
+    pub fn spiral(&self, radius: i32) -> Vec<Cube> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    // all hexes on the straight line between self and other, both included
+    pub fn line_to(&self, other: &Cube) -> Vec<Cube> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![*self];
+        }
+        // nudge the end points so that lines along hex edges always round to the same side
+        let (ax, ay, az) = (self.x as f64 + 1e-6, self.y as f64 + 2e-6, self.z as f64 - 3e-6);
+        let (bx, by, bz) = (other.x as f64 + 1e-6, other.y as f64 + 2e-6, other.z as f64 - 3e-6);
+        (0..=n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                Cube::round(ax + (bx - ax) * t, ay + (by - ay) * t, az + (bz - az) * t)
+            })
+            .collect()
+    }
+
+    pub fn round(x: f64, y: f64, z: f64) -> Cube {
+        let (mut rx, mut ry, mut rz) = (x.round(), y.round(), z.round());
+        let (dx, dy, dz) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+        if dx > dy && dx > dz {
+            rx = -ry - rz;
+        } else if dy > dz {
+            ry = -rx - rz;
+        } else {
+            rz = -rx - ry;
+        }
+        Cube::new(rx as i32, ry as i32, rz as i32)
+    }
+
+    pub fn to_axial(self) -> Axial {
+        Axial { q: self.x, r: self.z }
+    }
+
+    pub fn to_offset(self, layout: OffsetLayout) -> Offset {
+        self.to_axial().to_offset(layout)
+    }
+}
+
+// Axial ---------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Axial {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Axial {
+    pub fn new(q: i32, r: i32) -> Self {
+        Axial { q, r }
+    }
+
+    pub fn to_cube(self) -> Cube {
+        Cube::new(self.q, -self.q - self.r, self.r)
+    }
+
+    pub fn distance(&self, other: &Axial) -> i32 {
+        self.to_cube().distance(&other.to_cube())
+    }
+
+    pub fn to_offset(self, layout: OffsetLayout) -> Offset {
+        let (q, r) = (self.q, self.r);
+        match layout {
+            OffsetLayout::OddR => Offset { col: q + (r - (r & 1)) / 2, row: r },
+            OffsetLayout::EvenR => Offset { col: q + (r + (r & 1)) / 2, row: r },
+            OffsetLayout::OddQ => Offset { col: q, row: r + (q - (q & 1)) / 2 },
+            OffsetLayout::EvenQ => Offset { col: q, row: r + (q + (q & 1)) / 2 },
+        }
+    }
+}
+
+// Offset --------------------------------------------------------
+
+// OddR/EvenR shift every odd/even row of pointy-top hexes right by half a hex,
+// OddQ/EvenQ shift every odd/even column of flat-top hexes down.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Offset {
+    pub col: i32,
+    pub row: i32,
+}
+
+impl Offset {
+    pub fn new(col: i32, row: i32) -> Self {
+        Offset { col, row }
+    }
+
+    pub fn to_axial(self, layout: OffsetLayout) -> Axial {
+        let (col, row) = (self.col, self.row);
+        match layout {
+            OffsetLayout::OddR => Axial { q: col - (row - (row & 1)) / 2, r: row },
+            OffsetLayout::EvenR => Axial { q: col - (row + (row & 1)) / 2, r: row },
+            OffsetLayout::OddQ => Axial { q: col, r: row - (col - (col & 1)) / 2 },
+            OffsetLayout::EvenQ => Axial { q: col, r: row - (col + (col & 1)) / 2 },
+        }
+    }
+
+    pub fn to_cube(self, layout: OffsetLayout) -> Cube {
+        self.to_axial(layout).to_cube()
+    }
+}
+
+// Directions ----------------------------------------------------
+
+// neighbors of pointy-top hexes, clockwise starting east
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Direction {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 6] = [Direction::E, Direction::SE, Direction::SW, Direction::W, Direction::NW, Direction::NE];
+
+    pub fn offset(&self) -> Cube {
+        match self {
+            Direction::E => Cube::new(1, -1, 0),
+            Direction::SE => Cube::new(0, -1, 1),
+            Direction::SW => Cube::new(-1, 0, 1),
+            Direction::W => Cube::new(-1, 1, 0),
+            Direction::NW => Cube::new(0, 1, -1),
+            Direction::NE => Cube::new(1, 0, -1),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::E => "e",
+            Direction::SE => "se",
+            Direction::SW => "sw",
+            Direction::W => "w",
+            Direction::NW => "nw",
+            Direction::NE => "ne",
+        }
+    }
+
+    // 60 degree steps, clockwise
+    pub fn rotate(&self, steps: i32) -> Direction {
+        let index = Direction::ALL.iter().position(|d| d == self).unwrap() as i32;
+        Direction::ALL[(index + steps).rem_euclid(6) as usize]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.rotate(3)
+    }
+
+    // a sequence of directions without separators like "nwwswee"
+    pub fn parse_path(string: &str) -> Result<Vec<Direction>, String> {
+        parse_path(string)
+    }
+
+    pub fn follow(path: &[Direction]) -> Cube {
+        path.iter().fold(Cube::zero(), |c, d| c.neighbor(*d))
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Direction::ALL.iter().copied()
+            .find(|d| d.name() == s)
+            .ok_or(format!("unknown direction {}", s))
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// neighbors of flat-top hexes, clockwise starting north
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum FlatDirection {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl FlatDirection {
+    pub const ALL: [FlatDirection; 6] = [FlatDirection::N, FlatDirection::NE, FlatDirection::SE, FlatDirection::S, FlatDirection::SW, FlatDirection::NW];
+
+    pub fn offset(&self) -> Cube {
+        match self {
+            FlatDirection::N => Cube::new(0, 1, -1),
+            FlatDirection::NE => Cube::new(1, 0, -1),
+            FlatDirection::SE => Cube::new(1, -1, 0),
+            FlatDirection::S => Cube::new(0, -1, 1),
+            FlatDirection::SW => Cube::new(-1, 0, 1),
+            FlatDirection::NW => Cube::new(-1, 1, 0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlatDirection::N => "n",
+            FlatDirection::NE => "ne",
+            FlatDirection::SE => "se",
+            FlatDirection::S => "s",
+            FlatDirection::SW => "sw",
+            FlatDirection::NW => "nw",
+        }
+    }
+
+    pub fn rotate(&self, steps: i32) -> FlatDirection {
+        let index = FlatDirection::ALL.iter().position(|d| d == self).unwrap() as i32;
+        FlatDirection::ALL[(index + steps).rem_euclid(6) as usize]
+    }
+
+    pub fn opposite(&self) -> FlatDirection {
+        self.rotate(3)
+    }
+
+    // a sequence of directions without separators like "nnesws"
+    pub fn parse_path(string: &str) -> Result<Vec<FlatDirection>, String> {
+        parse_path(string)
+    }
+
+    pub fn follow(path: &[FlatDirection]) -> Cube {
+        path.iter().fold(Cube::zero(), |c, d| c + d.offset())
+    }
+}
+
+impl FromStr for FlatDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FlatDirection::ALL.iter().copied()
+            .find(|d| d.name() == s)
+            .ok_or(format!("unknown direction {}", s))
+    }
+}
+
+impl fmt::Display for FlatDirection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// splits a string into direction names, preferring two letter names over one letter ones
+fn parse_path<D>(string: &str) -> Result<Vec<D>, String> where D: FromStr<Err=String> {
+    let mut result = Vec::new();
+    let mut rest = string;
+    while !rest.is_empty() {
+        let two = rest.get(..2).and_then(|name| name.parse::<D>().ok());
+        match two {
+            Some(direction) => {
+                result.push(direction);
+                rest = &rest[2..];
+            }
+            None => {
+                let one = rest.get(..1).ok_or(format!("unexpected character in {}", string))?;
+                result.push(one.parse::<D>().map_err(|e| format!("{} in {}", e, string))?);
+                rest = &rest[1..];
+            }
+        }
+    }
+    Ok(result)
+}
@@ -25,37 +25,76 @@ mod dec_22;
 mod dec_23;
 mod dec_24;
 mod dec_25;
+mod aabb;
+mod assignment;
+mod automaton;
+mod bench;
+mod cli;
 mod common;
+mod graph;
+mod hashing;
+mod hex;
+mod matrices;
+mod neighborhood;
+mod packed;
+mod ray;
+mod pattern;
 mod vectors;
 
 fn main() {
+    let args = cli::Args::from_env();
+
+    // cargo run --release -- --bench
+    if args.flag("bench") {
+        bench::run();
+        return;
+    }
+
     println!("\nDecember 1st, 2020");
-    dec_01::part_one();
-    dec_01::part_two();
+    dec_01::part_one(&args);
+    dec_01::part_two(&args);
 
     println!("\nDecember 2nd, 2020");
     dec_02::part_one();
     dec_02::part_two();
+    if args.flag("audit") {
+        dec_02::audit(&args);
+    }
 
     println!("\nDecember 3rd, 2020");
     dec_03::part_one();
     dec_03::part_two();
+    if args.value("slope").is_some() || args.value("search").is_some() {
+        dec_03::explore(&args);
+    }
 
     println!("\nDecember 4th, 2020");
-    dec_04::part_one();
-    dec_04::part_two();
+    dec_04::part_one(&args);
+    dec_04::part_two(&args);
+    if args.value("export-jsonl").is_some() || args.value("export-csv").is_some() || args.flag("duplicates") {
+        dec_04::export(&args);
+    }
 
     println!("\nDecember 5th, 2020");
     dec_05::part_one();
     dec_05::part_two();
+    if args.flag("seat-map") || args.value("encode").is_some() {
+        dec_05::inspect(&args);
+    }
 
     println!("\nDecember 6th, 2020");
     dec_06::part_one();
     dec_06::part_two();
+    if ["at-least", "exactly", "divergent"].iter().any(|o| args.value(o).is_some()) || args.flag("histogram") {
+        dec_06::query(&args);
+    }
 
     println!("\nDecember 7th, 2020");
-    dec_07::part_one();
-    dec_07::part_two();
+    dec_07::part_one(&args);
+    dec_07::part_two(&args);
+    if args.flag("ancestors") || args.value("paths-to").is_some() || args.flag("deepest") {
+        dec_07::query(&args);
+    }
 
     println!("\nDecember 8th, 2020");
     dec_08::part_one();
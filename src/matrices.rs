@@ -0,0 +1,289 @@
+use std::ops::Mul;
+
+use ndarray::Array2;
+use num::{Num, Signed};
+
+use crate::vectors::{Vec2, Vec3};
+
+// Mat2 ----------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Mat2<T> {
+    pub rows: [[T; 2]; 2],
+}
+
+impl<T> Mul for Mat2<T> where T: Copy + Num {
+    type Output = Mat2<T>;
+    fn mul(self, other: Self) -> Self::Output {
+        let a = self.rows;
+        let b = other.rows;
+        Mat2::new(a[0][0] * b[0][0] + a[0][1] * b[1][0], a[0][0] * b[0][1] + a[0][1] * b[1][1],
+                  a[1][0] * b[0][0] + a[1][1] * b[1][0], a[1][0] * b[0][1] + a[1][1] * b[1][1])
+    }
+}
+
+impl<T> Mul<Vec2<T>> for Mat2<T> where T: Copy + Num {
+    type Output = Vec2<T>;
+    fn mul(self, v: Vec2<T>) -> Self::Output {
+        let m = self.rows;
+        Vec2::new(m[0][0] * v.x + m[0][1] * v.y, m[1][0] * v.x + m[1][1] * v.y)
+    }
+}
+
+impl<T> Mat2<T> where T: Copy + Num {
+    pub fn new(a: T, b: T, c: T, d: T) -> Self {
+        Mat2 { rows: [[a, b], [c, d]] }
+    }
+
+    pub fn identity() -> Self {
+        Mat2::new(T::one(), T::zero(), T::zero(), T::one())
+    }
+
+    // maps (x, y) to (-y, x) per quarter turn: counter-clockwise if y points up, clockwise if y points down
+    pub fn rotation(quarter_turns: i32) -> Self where T: Signed {
+        let (o, l) = (T::zero(), T::one());
+        match quarter_turns.rem_euclid(4) {
+            0 => Mat2::new(l, o, o, l),
+            1 => Mat2::new(o, -l, l, o),
+            2 => Mat2::new(-l, o, o, -l),
+            _ => Mat2::new(o, l, -l, o),
+        }
+    }
+
+    // rotation by a multiple of 90 degrees, None for any other angle
+    pub fn rotation_degrees(degrees: i32) -> Option<Self> where T: Signed {
+        if degrees % 90 == 0 {
+            Some(Mat2::rotation(degrees / 90))
+        } else {
+            None
+        }
+    }
+
+    // maps (x, y) to (-x, y)
+    pub fn mirror_x() -> Self where T: Signed {
+        Mat2::new(-T::one(), T::zero(), T::zero(), T::one())
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = self.rows;
+        Mat2::new(m[0][0], m[1][0], m[0][1], m[1][1])
+    }
+
+    pub fn det(&self) -> T {
+        let m = self.rows;
+        m[0][0] * m[1][1] - m[0][1] * m[1][0]
+    }
+
+    // exact inverse, only exists in integers if the determinant is 1 or -1
+    pub fn inverse(&self) -> Option<Self> where T: Signed {
+        let det = self.det();
+        if !det.abs().is_one() {
+            return None;
+        }
+        let m = self.rows;
+        Some(Mat2::new(m[1][1] * det, -m[0][1] * det, -m[1][0] * det, m[0][0] * det))
+    }
+}
+
+// Mat3 ----------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Mat3<T> {
+    pub rows: [[T; 3]; 3],
+}
+
+impl<T> Mul for Mat3<T> where T: Copy + Num {
+    type Output = Mat3<T>;
+    fn mul(self, other: Self) -> Self::Output {
+        let mut rows = [[T::zero(); 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).fold(T::zero(), |sum, k| sum + self.rows[i][k] * other.rows[k][j]);
+            }
+        }
+        Mat3 { rows }
+    }
+}
+
+impl<T> Mul<Vec3<T>> for Mat3<T> where T: Copy + Num {
+    type Output = Vec3<T>;
+    fn mul(self, v: Vec3<T>) -> Self::Output {
+        let m = self.rows;
+        Vec3::new(m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+                  m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+                  m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z)
+    }
+}
+
+impl<T> Mat3<T> where T: Copy + Num {
+    pub fn new(rows: [[T; 3]; 3]) -> Self {
+        Mat3 { rows }
+    }
+
+    pub fn identity() -> Self {
+        let (o, l) = (T::zero(), T::one());
+        Mat3::new([[l, o, o], [o, l, o], [o, o, l]])
+    }
+
+    // quarter turns around the given axis (0 = x, 1 = y, 2 = z), right handed
+    pub fn rotation(axis: usize, quarter_turns: i32) -> Self where T: Signed {
+        let plane = Mat2::<T>::rotation(quarter_turns).rows;
+        let (a, b) = match axis {
+            0 => (1, 2),
+            1 => (2, 0),
+            _ => (0, 1),
+        };
+        let mut m = Mat3::identity();
+        m.rows[a][a] = plane[0][0];
+        m.rows[a][b] = plane[0][1];
+        m.rows[b][a] = plane[1][0];
+        m.rows[b][b] = plane[1][1];
+        m
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = self.rows;
+        Mat3::new([[m[0][0], m[1][0], m[2][0]], [m[0][1], m[1][1], m[2][1]], [m[0][2], m[1][2], m[2][2]]])
+    }
+
+    pub fn det(&self) -> T {
+        let m = self.rows;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    // exact inverse via the adjugate, only exists in integers if the determinant is 1 or -1
+    pub fn inverse(&self) -> Option<Self> where T: Signed {
+        let det = self.det();
+        if !det.abs().is_one() {
+            return None;
+        }
+        let m = self.rows;
+        let cofactor = |r: usize, c: usize| {
+            let (r1, r2) = ((r + 1) % 3, (r + 2) % 3);
+            let (c1, c2) = ((c + 1) % 3, (c + 2) % 3);
+            m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+        };
+        let mut rows = [[T::zero(); 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = cofactor(j, i) * det;
+            }
+        }
+        Some(Mat3 { rows })
+    }
+}
+
+// D4 ------------------------------------------------------------
+
+// One of the 8 symmetries of a square: first mirror along x (if flipped), then rotate.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct D4 {
+    quarter_turns: u8,
+    flipped: bool,
+}
+
+impl D4 {
+    pub fn identity() -> Self {
+        D4 { quarter_turns: 0, flipped: false }
+    }
+
+    pub fn rotation(quarter_turns: i32) -> Self {
+        D4 { quarter_turns: quarter_turns.rem_euclid(4) as u8, flipped: false }
+    }
+
+    pub fn mirror_x() -> Self {
+        D4 { quarter_turns: 0, flipped: true }
+    }
+
+    // the 4 rotations followed by the 4 mirrored rotations
+    pub fn all() -> [D4; 8] {
+        let mut all = [D4::identity(); 8];
+        for (i, symmetry) in all.iter_mut().enumerate() {
+            *symmetry = D4 { quarter_turns: (i % 4) as u8, flipped: i >= 4 };
+        }
+        all
+    }
+
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    pub fn quarter_turns(&self) -> i32 {
+        self.quarter_turns as i32
+    }
+
+    pub fn matrix(&self) -> Mat2<i32> {
+        let rotation = Mat2::rotation(self.quarter_turns());
+        if self.flipped {
+            rotation * Mat2::mirror_x()
+        } else {
+            rotation
+        }
+    }
+
+    // self after other
+    pub fn compose(&self, other: &D4) -> D4 {
+        // a mirror reverses the direction of the rotations applied before it
+        let turns = if self.flipped { self.quarter_turns() - other.quarter_turns() } else { self.quarter_turns() + other.quarter_turns() };
+        D4 { quarter_turns: turns.rem_euclid(4) as u8, flipped: self.flipped != other.flipped }
+    }
+
+    pub fn inverse(&self) -> D4 {
+        if self.flipped {
+            *self
+        } else {
+            D4::rotation(-self.quarter_turns())
+        }
+    }
+
+    pub fn apply(&self, point: Vec2<i32>) -> Vec2<i32> {
+        self.matrix() * point
+    }
+
+    pub fn transformed_size(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.quarter_turns % 2 == 1 { (height, width) } else { (width, height) }
+    }
+
+    // the position a cell of a width x height grid ends up at
+    fn grid_mapping(&self, width: usize, height: usize) -> impl Fn(usize, usize) -> (usize, usize) {
+        let matrix = self.matrix();
+        let corner = matrix * Vec2::new(width as i32 - 1, height as i32 - 1);
+        let offset = Vec2::new(-corner.x.min(0), -corner.y.min(0));
+        move |x: usize, y: usize| {
+            let p = matrix * Vec2::new(x as i32, y as i32) + offset;
+            (p.x as usize, p.y as usize)
+        }
+    }
+
+    // rows are y, columns are x
+    pub fn apply_to_grid<T>(&self, grid: &[Vec<T>]) -> Vec<Vec<T>> where T: Clone {
+        let height = grid.len();
+        let width = grid.first().map(|row| row.len()).unwrap_or(0);
+        let (new_width, new_height) = self.transformed_size(width, height);
+        let source = self.inverse().grid_mapping(new_width, new_height);
+        (0..new_height)
+            .map(|y| (0..new_width).map(|x| {
+                let (sx, sy) = source(x, y);
+                grid[sy][sx].clone()
+            }).collect())
+            .collect()
+    }
+
+    // indexed [row, column] like everywhere else in ndarray, i.e. [y, x]
+    pub fn apply_to_array<T>(&self, array: &Array2<T>) -> Array2<T> where T: Clone {
+        let (height, width) = array.dim();
+        let (new_width, new_height) = self.transformed_size(width, height);
+        let source = self.inverse().grid_mapping(new_width, new_height);
+        Array2::from_shape_fn((new_height, new_width), |(y, x)| {
+            let (sx, sy) = source(x, y);
+            array[[sy, sx]].clone()
+        })
+    }
+
+    // the first symmetry that turns from into to, if there is any
+    pub fn find<T>(from: &Array2<T>, to: &Array2<T>) -> Option<D4> where T: Clone + PartialEq {
+        D4::all().iter().copied().find(|symmetry| symmetry.apply_to_array(from) == *to)
+    }
+}
@@ -0,0 +1,96 @@
+use crate::aabb::Aabb;
+use crate::vectors::VecN;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    // every cell whose components all differ by at most radius
+    Moore,
+    // every cell within a manhattan distance of radius
+    VonNeumann,
+}
+
+// Iterates over the cells around center without allocating. The center itself is skipped
+// unless with_center is used.
+#[derive(Clone, Debug)]
+pub struct Neighborhood<const N: usize> {
+    kind: Kind,
+    center: VecN<i32, N>,
+    radius: i32,
+    include_center: bool,
+    bounds: Option<Aabb<i32, N>>,
+    offset: VecN<i32, N>,
+    done: bool,
+}
+
+pub fn moore<const N: usize>(center: VecN<i32, N>, radius: i32) -> Neighborhood<N> {
+    Neighborhood::new(Kind::Moore, center, radius)
+}
+
+pub fn von_neumann<const N: usize>(center: VecN<i32, N>, radius: i32) -> Neighborhood<N> {
+    Neighborhood::new(Kind::VonNeumann, center, radius)
+}
+
+impl<const N: usize> Neighborhood<N> {
+    pub fn new(kind: Kind, center: VecN<i32, N>, radius: i32) -> Self {
+        Neighborhood {
+            kind,
+            center,
+            radius,
+            include_center: false,
+            bounds: None,
+            offset: VecN::splat(-radius),
+            done: radius < 0 || N == 0,
+        }
+    }
+
+    pub fn with_center(mut self) -> Self {
+        self.include_center = true;
+        self
+    }
+
+    // only yields cells inside bounds
+    pub fn clipped(mut self, bounds: Aabb<i32, N>) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    fn accepts(&self, offset: &VecN<i32, N>) -> bool {
+        if !self.include_center && offset.iter().all(|c| *c == 0) {
+            return false;
+        }
+        if self.kind == Kind::VonNeumann && offset.iter().map(|c| c.abs()).sum::<i32>() > self.radius {
+            return false;
+        }
+        match &self.bounds {
+            Some(bounds) => bounds.contains(&(self.center + *offset)),
+            None => true,
+        }
+    }
+
+    // odometer style, the first component changes fastest
+    fn advance(&mut self) {
+        for i in 0..N {
+            if self.offset[i] < self.radius {
+                self.offset[i] += 1;
+                return;
+            }
+            self.offset[i] = -self.radius;
+        }
+        self.done = true;
+    }
+}
+
+impl<const N: usize> Iterator for Neighborhood<N> {
+    type Item = VecN<i32, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let offset = self.offset;
+            self.advance();
+            if self.accepts(&offset) {
+                return Some(self.center + offset);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,80 @@
+use std::convert::TryFrom;
+
+use crate::automaton::Cell;
+use crate::vectors::VecN;
+
+// Lossless packing of small integer vectors into a single integer key, which hashes and compares
+// much faster than the vector itself. Every component gets BITS bits and is stored with a bias,
+// so pack(a + b) == pack(a) + pack(b) - pack(0) as long as no component leaves the range.
+macro_rules! packed_key {
+    ($name:ident, $key:ty) => {
+        #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name<const N: usize>(pub $key);
+
+        impl<const N: usize> $name<N> {
+            pub const BITS: u32 = <$key>::BITS / N as u32;
+            // any i32 fits once there are 32 bits
+            const BIAS: i64 = 1 << ((if Self::BITS < 32 { Self::BITS } else { 32 }) - 1);
+            const MASK: $key = <$key>::MAX >> (<$key>::BITS - Self::BITS);
+            const ZERO: $key = {
+                let mut key = 0;
+                let mut i = 0;
+                while i < N {
+                    key |= (Self::BIAS as $key) << (i as u32 * Self::BITS);
+                    i += 1;
+                }
+                key
+            };
+
+            // smallest and largest component that can be packed
+            pub const MIN: i64 = -Self::BIAS;
+            pub const MAX: i64 = Self::BIAS - 1;
+
+            // None if a component does not fit into BITS bits
+            pub fn pack(v: &VecN<i32, N>) -> Option<Self> {
+                let mut key: $key = 0;
+                for (i, c) in v.iter().enumerate() {
+                    let c = *c as i64;
+                    if c < Self::MIN || c > Self::MAX {
+                        return None;
+                    }
+                    key |= ((c + Self::BIAS) as $key) << (i as u32 * Self::BITS);
+                }
+                Some($name(key))
+            }
+
+            pub fn unpack(&self) -> VecN<i32, N> {
+                let mut v = VecN::zero();
+                for i in 0..N {
+                    let field = (self.0 >> (i as u32 * Self::BITS)) & Self::MASK;
+                    v[i] = (field as i64 - Self::BIAS) as i32;
+                }
+                v
+            }
+        }
+
+        impl<const N: usize> TryFrom<VecN<i32, N>> for $name<N> {
+            type Error = String;
+
+            fn try_from(v: VecN<i32, N>) -> Result<Self, Self::Error> {
+                $name::pack(&v).ok_or(format!("{} does not fit into {} bits per component", v, Self::BITS))
+            }
+        }
+
+        impl<const N: usize> From<$name<N>> for VecN<i32, N> {
+            fn from(key: $name<N>) -> Self {
+                key.unpack()
+            }
+        }
+
+        // wraps silently if a component leaves the packable range
+        impl<const N: usize> Cell for $name<N> {
+            fn offset(&self, by: &Self) -> Self {
+                $name(self.0.wrapping_add(by.0).wrapping_sub(Self::ZERO))
+            }
+        }
+    };
+}
+
+packed_key!(Packed64, u64);
+packed_key!(Packed128, u128);
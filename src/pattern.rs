@@ -0,0 +1,95 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternError {
+    pub input: String,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {} of \"{}\"", self.message, self.column + 1, self.input)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+// Splits input along the literal parts of a scanf-like pattern where every `{}` is a field.
+// A field extends up to the first occurrence of the literal following it, the last one to the
+// end of the line. Returns the column and the text of every field.
+pub fn split<'a>(input: &'a str, pattern: &str, field_names: &[&str]) -> Result<Vec<(usize, &'a str)>, PatternError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    assert_eq!(literals.len(), field_names.len() + 1, "pattern \"{}\" does not match the number of fields", pattern);
+
+    let error = |column: usize, message: String| PatternError { input: input.to_string(), column, message };
+
+    let mut values = Vec::new();
+    let mut column = 0;
+    if !input.starts_with(literals[0]) {
+        return Err(error(0, format!("expected \"{}\"", literals[0])));
+    }
+    column += literals[0].len();
+
+    for (i, name) in field_names.iter().enumerate() {
+        let literal = literals[i + 1];
+        let rest = &input[column..];
+        let end = if i + 1 == field_names.len() {
+            if !rest.ends_with(literal) {
+                return Err(error(input.len(), format!("expected \"{}\" after field {}", literal, name)));
+            }
+            rest.len() - literal.len()
+        } else if literal.is_empty() {
+            return Err(error(column, format!("fields {} and {} are not separated", name, field_names[i + 1])));
+        } else {
+            match rest.find(literal) {
+                Some(end) => end,
+                None => return Err(error(input.len(), format!("expected \"{}\" after field {}", literal, name))),
+            }
+        };
+        if end == 0 {
+            return Err(error(column, format!("field {} is empty", name)));
+        }
+        values.push((column, &rest[..end]));
+        column += end + literal.len();
+    }
+    Ok(values)
+}
+
+pub fn parse_field<T>(input: &str, (column, value): (usize, &str), name: &str) -> Result<T, PatternError> where T: FromStr, T::Err: fmt::Display {
+    value.parse::<T>().map_err(|e| PatternError {
+        input: input.to_string(),
+        column,
+        message: format!("field {} (\"{}\"): {}", name, value, e),
+    })
+}
+
+// Declares a struct together with a FromStr implementation that reads its fields in order:
+//
+// line_pattern! {
+//     #[pattern("{}-{} {}: {}")]
+//     struct PasswordEntry { min: i32, max: i32, character: char, password: String }
+// }
+macro_rules! line_pattern {
+    (#[pattern($pattern:literal)] $(#[$meta:meta])* $vis:vis struct $name:ident { $($field_vis:vis $field:ident: $type:ty),* $(,)? }) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($field_vis $field: $type),*
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = crate::pattern::PatternError;
+
+            fn from_str(input: &str) -> Result<Self, Self::Err> {
+                let values = crate::pattern::split(input, $pattern, &[$(stringify!($field)),*])?;
+                let mut values = values.into_iter();
+                Ok($name {
+                    $($field: crate::pattern::parse_field(input, values.next().unwrap(), stringify!($field))?),*
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use line_pattern;
@@ -0,0 +1,105 @@
+use crate::aabb::Aabb;
+use crate::vectors::VecN;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Hit<const N: usize> {
+    pub position: VecN<i32, N>,
+    // number of steps taken from the start
+    pub distance: u32,
+}
+
+// Steps from start in a fixed direction. The start itself is not visited; the ray ends when it
+// leaves its bounds or after max_steps, otherwise it goes on forever.
+#[derive(Clone, Debug)]
+pub struct Ray<const N: usize> {
+    position: VecN<i32, N>,
+    direction: VecN<i32, N>,
+    bounds: Option<Aabb<i32, N>>,
+    max_steps: Option<u32>,
+    steps: u32,
+}
+
+pub fn ray<const N: usize>(start: VecN<i32, N>, direction: VecN<i32, N>) -> Ray<N> {
+    Ray {
+        position: start,
+        direction,
+        bounds: None,
+        max_steps: None,
+        steps: 0,
+    }
+}
+
+impl<const N: usize> Ray<N> {
+    pub fn within(mut self, bounds: Aabb<i32, N>) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    // the first position the predicate holds for
+    pub fn cast<F>(self, predicate: F) -> Option<Hit<N>> where F: Fn(VecN<i32, N>) -> bool {
+        self.enumerate()
+            .find(|(_, p)| predicate(*p))
+            .map(|(i, position)| Hit { position, distance: i as u32 + 1 })
+    }
+}
+
+impl<const N: usize> Iterator for Ray<N> {
+    type Item = VecN<i32, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.direction == VecN::zero() || self.max_steps.is_some_and(|max| self.steps >= max) {
+            return None;
+        }
+        let next = self.position + self.direction;
+        if let Some(bounds) = &self.bounds {
+            if !bounds.contains(&next) {
+                return None;
+            }
+        }
+        self.position = next;
+        self.steps += 1;
+        Some(next)
+    }
+}
+
+// Bresenham's line in any dimension, from and to included
+pub fn line<const N: usize>(from: VecN<i32, N>, to: VecN<i32, N>) -> Vec<VecN<i32, N>> {
+    let delta = to - from;
+    let abs = delta.abs();
+    let signs = delta.signum();
+    let steps = abs.iter().copied().max().unwrap_or(0);
+
+    let mut errors = abs.map(|d| 2 * d - steps);
+    let mut position = from;
+    let mut result = Vec::with_capacity(steps as usize + 1);
+    result.push(position);
+    for _ in 0..steps {
+        for i in 0..N {
+            if abs[i] == steps {
+                position[i] += signs[i];
+                continue;
+            }
+            if errors[i] > 0 {
+                position[i] += signs[i];
+                errors[i] -= 2 * steps;
+            }
+            errors[i] += 2 * abs[i];
+        }
+        result.push(position);
+    }
+    result
+}
+
+// the first point after from on the line to to that the predicate holds for
+pub fn cast_line<F, const N: usize>(from: VecN<i32, N>, to: VecN<i32, N>, predicate: F) -> Option<Hit<N>> where F: Fn(VecN<i32, N>) -> bool {
+    line(from, to).into_iter()
+        .enumerate()
+        .skip(1)
+        .find(|(_, p)| predicate(*p))
+        .map(|(i, position)| Hit { position, distance: i as u32 })
+}
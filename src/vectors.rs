@@ -1,294 +1,491 @@
+use std::convert::TryInto;
 use std::fmt;
-use std::ops::{Add, Div, Mul, Sub};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Deref, DerefMut, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+use std::str::FromStr;
 
-use num::{Float, Signed};
+use num::traits::Euclid;
+use num::{Float, Integer, Signed, Zero};
 
-// Vec1 ----------------------------------------------------------
+// VecN ----------------------------------------------------------
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
-pub struct Vec1<T> where T: Clone {
-    pub x: T,
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VecN<T, const N: usize> {
+    pub components: [T; N],
 }
 
-impl<T> Add for Vec1<T> where T: Add<Output=T>, T: Clone {
-    type Output = Vec1<T>;
+pub type Vec1<T> = VecN<T, 1>;
+pub type Vec2<T> = VecN<T, 2>;
+pub type Vec3<T> = VecN<T, 3>;
+pub type Vec4<T> = VecN<T, 4>;
+
+impl<T, const N: usize> Default for VecN<T, N> where T: Copy + Default {
+    fn default() -> Self {
+        VecN { components: [T::default(); N] }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for VecN<T, N> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        &self.components[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for VecN<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.components[index]
+    }
+}
+
+impl<T, const N: usize> Add for VecN<T, N> where T: Copy + Add<Output=T> {
+    type Output = VecN<T, N>;
     fn add(self, other: Self) -> Self::Output {
-        Vec1::new(self.x + other.x)
+        self.zip_map(&other, |a, b| a + b)
     }
 }
 
-impl<T> Sub for Vec1<T> where T: Sub<Output=T>, T: Clone {
-    type Output = Vec1<T>;
+impl<T, const N: usize> Sub for VecN<T, N> where T: Copy + Sub<Output=T> {
+    type Output = VecN<T, N>;
     fn sub(self, other: Self) -> Self::Output {
-        Vec1::new(self.x - other.x)
+        self.zip_map(&other, |a, b| a - b)
     }
 }
 
-impl<T> Mul<T> for Vec1<T> where T: Mul<Output=T>, T: Clone {
-    type Output = Vec1<T>;
+impl<T, const N: usize> Mul<T> for VecN<T, N> where T: Copy + Mul<Output=T> {
+    type Output = VecN<T, N>;
     fn mul(self, other: T) -> Self::Output {
-        Vec1::new(self.x * other.clone())
+        self.map(|a| a * other)
     }
 }
 
-impl<T> Div<T> for Vec1<T> where T: Div<Output=T>, T: Clone {
-    type Output = Vec1<T>;
+impl<T, const N: usize> Div<T> for VecN<T, N> where T: Copy + Div<Output=T> {
+    type Output = VecN<T, N>;
     fn div(self, other: T) -> Self::Output {
-        Vec1::new(self.x / other.clone())
+        self.map(|a| a / other)
     }
 }
 
-impl<T> Vec1<T> where T: Clone {
-    pub fn new(x: T) -> Self {
-        Vec1 { x }
+// component-wise
+impl<T, const N: usize> Mul for VecN<T, N> where T: Copy + Mul<Output=T> {
+    type Output = VecN<T, N>;
+    fn mul(self, other: Self) -> Self::Output {
+        self.zip_map(&other, |a, b| a * b)
     }
+}
 
-    pub fn zero() -> Vec1<T> where T: Default {
-        Default::default()
+// component-wise
+impl<T, const N: usize> Div for VecN<T, N> where T: Copy + Div<Output=T> {
+    type Output = VecN<T, N>;
+    fn div(self, other: Self) -> Self::Output {
+        self.zip_map(&other, |a, b| a / b)
     }
+}
 
-    pub fn mag(&self) -> T where T: Float {
-        self.sqr_mag().sqrt()
+// Euclidean remainder, so the result always lies in 0..other for a toroidal map of size other
+impl<T, const N: usize> Rem for VecN<T, N> where T: Copy + Euclid {
+    type Output = VecN<T, N>;
+    fn rem(self, other: Self) -> Self::Output {
+        self.zip_map(&other, |a, b| a.rem_euclid(&b))
     }
+}
 
-    pub fn sqr_mag(&self) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * self.x.clone()
+impl<T, const N: usize> Rem<T> for VecN<T, N> where T: Copy + Euclid {
+    type Output = VecN<T, N>;
+    fn rem(self, other: T) -> Self::Output {
+        self.map(|a| a.rem_euclid(&other))
     }
+}
 
-    pub fn dist(&self, other: &Vec1<T>) -> T where T: Float {
-        (self.clone() - other.clone()).mag()
+impl<T, const N: usize> Neg for VecN<T, N> where T: Copy + Neg<Output=T> {
+    type Output = VecN<T, N>;
+    fn neg(self) -> Self::Output {
+        self.map(|a| -a)
     }
+}
 
-    pub fn dist_manhattan(&self, other: &Vec1<T>) -> T where T: Signed {
-        (self.x.clone() - other.x.clone()).abs()
+impl<T, const N: usize> AddAssign for VecN<T, N> where T: Copy + Add<Output=T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
     }
+}
 
-    pub fn dot(&self, other: &Vec1<T>) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * other.x.clone()
+impl<T, const N: usize> SubAssign for VecN<T, N> where T: Copy + Sub<Output=T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
     }
 }
 
-impl<T> fmt::Display for Vec1<T> where T: fmt::Debug, T: Clone {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self.x)
+impl<T, const N: usize> MulAssign<T> for VecN<T, N> where T: Copy + Mul<Output=T> {
+    fn mul_assign(&mut self, other: T) {
+        *self = *self * other;
     }
 }
 
-// Vec2 ----------------------------------------------------------
+impl<T, const N: usize> MulAssign for VecN<T, N> where T: Copy + Mul<Output=T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
-pub struct Vec2<T> where T: Clone {
-    pub x: T,
-    pub y: T,
+impl<T, const N: usize> DivAssign<T> for VecN<T, N> where T: Copy + Div<Output=T> {
+    fn div_assign(&mut self, other: T) {
+        *self = *self / other;
+    }
 }
 
-impl<T> Add for Vec2<T> where T: Add<Output=T>, T: Clone {
-    type Output = Vec2<T>;
-    fn add(self, other: Self) -> Self::Output {
-        Vec2::new(self.x + other.x, self.y + other.y)
+impl<T, const N: usize> DivAssign for VecN<T, N> where T: Copy + Div<Output=T> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
     }
 }
 
-impl<T> Sub for Vec2<T> where T: Sub<Output=T>, T: Clone {
-    type Output = Vec2<T>;
-    fn sub(self, other: Self) -> Self::Output {
-        Vec2::new(self.x - other.x, self.y - other.y)
+impl<T, const N: usize> Sum for VecN<T, N> where T: Copy + Zero {
+    fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+        iter.fold(VecN::zero(), |sum, v| sum + v)
     }
 }
 
-impl<T> Mul<T> for Vec2<T> where T: Mul<Output=T>, T: Clone {
-    type Output = Vec2<T>;
-    fn mul(self, other: T) -> Self::Output {
-        Vec2::new(self.x * other.clone(), self.y * other.clone())
+impl<'a, T, const N: usize> Sum<&'a VecN<T, N>> for VecN<T, N> where T: Copy + Zero {
+    fn sum<I: Iterator<Item=&'a Self>>(iter: I) -> Self {
+        iter.fold(VecN::zero(), |sum, v| sum + *v)
     }
 }
 
-impl<T> Div<T> for Vec2<T> where T: Div<Output=T>, T: Clone {
-    type Output = Vec2<T>;
-    fn div(self, other: T) -> Self::Output {
-        Vec2::new(self.x / other.clone(), self.y / other.clone())
+impl<T, const N: usize> From<[T; N]> for VecN<T, N> {
+    fn from(components: [T; N]) -> Self {
+        VecN { components }
     }
 }
 
-impl<T> Vec2<T> where T: Clone {
-    pub fn new(x: T, y: T) -> Self {
-        Vec2 { x, y }
+impl<T, const N: usize> From<VecN<T, N>> for [T; N] {
+    fn from(v: VecN<T, N>) -> Self {
+        v.components
     }
+}
 
-    pub fn zero() -> Vec2<T> where T: Default {
-        Default::default()
+impl<T, const N: usize> VecN<T, N> where T: Copy {
+    pub const fn from_array(components: [T; N]) -> Self {
+        VecN { components }
     }
 
-    pub fn mag(&self) -> T where T: Float {
-        self.sqr_mag().sqrt()
+    pub fn splat(value: T) -> Self {
+        VecN { components: [value; N] }
     }
 
-    pub fn sqr_mag(&self) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * self.x.clone() + self.y.clone() * self.y.clone()
+    pub fn zero() -> Self where T: Zero {
+        VecN::splat(T::zero())
     }
 
-    pub fn dist(&self, other: &Vec2<T>) -> T where T: Float {
-        (self.clone() - other.clone()).mag()
+    pub fn map<U, F>(&self, f: F) -> VecN<U, N> where F: Fn(T) -> U {
+        VecN { components: std::array::from_fn(|i| f(self.components[i])) }
     }
 
-    pub fn dist_manhattan(&self, other: &Vec2<T>) -> T where T: Signed {
-        (self.x.clone() - other.x.clone()).abs() + (self.y.clone() - other.y.clone()).abs()
+    pub fn zip_map<U, F>(&self, other: &Self, f: F) -> VecN<U, N> where F: Fn(T, T) -> U {
+        VecN { components: std::array::from_fn(|i| f(self.components[i], other.components[i])) }
     }
 
-    pub fn dot(&self, other: &Vec2<T>) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * other.x.clone() + self.y.clone() * other.y.clone()
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.components.iter()
     }
-}
 
-impl<T> fmt::Display for Vec2<T> where T: fmt::Debug, T: Clone {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+    pub fn mag(&self) -> T where T: Float {
+        self.sqr_mag().sqrt()
     }
-}
 
-// Vec3 ----------------------------------------------------------
+    pub fn sqr_mag(&self) -> T where T: Zero + Mul<Output=T> {
+        self.dot(self)
+    }
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
-pub struct Vec3<T> where T: Clone {
-    pub x: T,
-    pub y: T,
-    pub z: T,
-}
+    pub fn dist(&self, other: &Self) -> T where T: Float {
+        (*self - *other).mag()
+    }
 
-impl<T> Add for Vec3<T> where T: Add<Output=T>, T: Clone {
-    type Output = Vec3<T>;
-    fn add(self, other: Self) -> Self::Output {
-        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    pub fn dist_manhattan(&self, other: &Self) -> T where T: Signed {
+        self.iter().zip(other.iter()).fold(T::zero(), |sum, (a, b)| sum + (*a - *b).abs())
     }
-}
 
-impl<T> Sub for Vec3<T> where T: Sub<Output=T>, T: Clone {
-    type Output = Vec3<T>;
-    fn sub(self, other: Self) -> Self::Output {
-        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    pub fn dot(&self, other: &Self) -> T where T: Zero + Mul<Output=T> {
+        self.iter().zip(other.iter()).fold(T::zero(), |sum, (a, b)| sum + *a * *b)
     }
-}
 
-impl<T> Mul<T> for Vec3<T> where T: Mul<Output=T>, T: Clone {
-    type Output = Vec3<T>;
-    fn mul(self, other: T) -> Self::Output {
-        Vec3::new(self.x * other.clone(), self.y * other.clone(), self.z * other.clone())
+    // the largest difference along any axis, i.e. the number of king moves
+    pub fn dist_chebyshev(&self, other: &Self) -> T where T: Signed + PartialOrd {
+        self.iter().zip(other.iter()).fold(T::zero(), |max, (a, b)| {
+            let d = (*a - *b).abs();
+            if d > max { d } else { max }
+        })
     }
-}
 
-impl<T> Div<T> for Vec3<T> where T: Div<Output=T>, T: Clone {
-    type Output = Vec3<T>;
-    fn div(self, other: T) -> Self::Output {
-        Vec3::new(self.x / other.clone(), self.y / other.clone(), self.z / other.clone())
+    pub fn abs(&self) -> Self where T: Signed {
+        self.map(|c| c.abs())
     }
-}
 
-impl<T> Vec3<T> where T: Clone {
-    pub fn new(x: T, y: T, z: T) -> Self {
-        Vec3 { x, y, z }
+    pub fn signum(&self) -> Self where T: Signed {
+        self.map(|c| c.signum())
     }
 
-    pub fn zero() -> Vec3<T> where T: Default {
-        Default::default()
+    // the shortest integer vector pointing the same way, e.g. (4, -6) becomes (2, -3)
+    pub fn unit_direction(&self) -> Self where T: Integer {
+        let gcd = self.iter().fold(T::zero(), |gcd, c| gcd.gcd(c));
+        if gcd.is_zero() {
+            *self
+        } else {
+            self.map(|c| c / gcd)
+        }
     }
 
-    pub fn mag(&self) -> T where T: Float {
-        self.sqr_mag().sqrt()
+    pub fn component_min(&self, other: &Self) -> Self where T: PartialOrd {
+        self.zip_map(other, |a, b| if b < a { b } else { a })
     }
 
-    pub fn sqr_mag(&self) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * self.x.clone() + self.y.clone() * self.y.clone() + self.z.clone() * self.z.clone()
+    pub fn component_max(&self, other: &Self) -> Self where T: PartialOrd {
+        self.zip_map(other, |a, b| if b > a { b } else { a })
     }
 
-    pub fn dist(&self, other: &Vec3<T>) -> T where T: Float {
-        (self.clone() - other.clone()).mag()
+    pub fn component_clamp(&self, min: &Self, max: &Self) -> Self where T: PartialOrd {
+        self.component_max(min).component_min(max)
     }
 
-    pub fn dist_manhattan(&self, other: &Vec3<T>) -> T where T: Signed {
-        (self.x.clone() - other.x.clone()).abs() + (self.y.clone() - other.y.clone()).abs() + (self.z.clone() - other.z.clone()).abs()
+    // angle between the two vectors in radians, in 0..=pi
+    pub fn angle_to(&self, other: &Self) -> T where T: Float {
+        let cos = self.dot(other) / (self.mag() * other.mag());
+        cos.max(-T::one()).min(T::one()).acos()
     }
 
-    pub fn dot(&self, other: &Vec3<T>) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * other.x.clone() + self.y.clone() * other.y.clone() + self.z.clone() * other.z.clone()
+    // t = 0 gives self, t = 1 gives other
+    pub fn lerp(&self, other: &Self, t: T) -> Self where T: Float {
+        self.zip_map(other, |a, b| a + (b - a) * t)
     }
 }
 
-impl<T> fmt::Display for Vec3<T> where T: fmt::Debug, T: Clone {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+impl<T> VecN<T, 2> where T: Copy {
+    // z component of the cross product of the two vectors extended to 3D
+    pub fn perp_dot(&self, other: &Self) -> T where T: Mul<Output=T> + Sub<Output=T> {
+        self.x * other.y - self.y * other.x
     }
-}
 
-// Vec4 ----------------------------------------------------------
+    // the vector rotated by 90 degrees, (x, y) becomes (-y, x)
+    pub fn perp(&self) -> Self where T: Neg<Output=T> {
+        Vec2::new(-self.y, self.x)
+    }
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
-pub struct Vec4<T> where T: Clone {
-    pub x: T,
-    pub y: T,
-    pub z: T,
-    pub w: T,
+    // angle to the x axis in radians, in -pi..=pi
+    pub fn atan2(&self) -> T where T: Float {
+        self.y.atan2(self.x)
+    }
 }
 
-impl<T> Add for Vec4<T> where T: Add<Output=T>, T: Clone {
-    type Output = Vec4<T>;
-    fn add(self, other: Self) -> Self::Output {
-        Vec4::new(self.x + other.x, self.y + other.y, self.z + other.z, self.w + other.w)
+impl<T> VecN<T, 3> where T: Copy {
+    pub fn cross(&self, other: &Self) -> Self where T: Mul<Output=T> + Sub<Output=T> {
+        Vec3::new(self.y * other.z - self.z * other.y,
+                  self.z * other.x - self.x * other.z,
+                  self.x * other.y - self.y * other.x)
     }
 }
 
-impl<T> Sub for Vec4<T> where T: Sub<Output=T>, T: Clone {
-    type Output = Vec4<T>;
-    fn sub(self, other: Self) -> Self::Output {
-        Vec4::new(self.x - other.x, self.y - other.y, self.z - other.z, self.w - other.w)
-    }
+const NAMES: [char; 4] = ['x', 'y', 'z', 'w'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    // (1, -2, 3)
+    Tuple,
+    // [1, -2, 3]
+    List,
+    // 1,-2,3
+    Csv,
+    // <x=1, y=-2, z=3>
+    Named,
 }
 
-impl<T> Mul<T> for Vec4<T> where T: Mul<Output=T>, T: Clone {
-    type Output = Vec4<T>;
-    fn mul(self, other: T) -> Self::Output {
-        Vec4::new(self.x * other.clone(), self.y * other.clone(), self.z * other.clone(), self.w * other.clone())
+pub struct Styled<'a, T, const N: usize> {
+    vec: &'a VecN<T, N>,
+    style: Style,
+}
+
+impl<'a, T, const N: usize> fmt::Display for Styled<'a, T, N> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (open, separator, close) = match self.style {
+            Style::Tuple => ("(", ", ", ")"),
+            Style::List => ("[", ", ", "]"),
+            Style::Csv => ("", ",", ""),
+            Style::Named => ("<", ", ", ">"),
+        };
+        write!(f, "{}", open)?;
+        for (i, c) in self.vec.components.iter().enumerate() {
+            if i > 0 {
+                write!(f, "{}", separator)?;
+            }
+            if self.style == Style::Named {
+                match NAMES.get(i) {
+                    Some(name) => write!(f, "{}=", name)?,
+                    None => write!(f, "c{}=", i)?,
+                }
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, "{}", close)
     }
 }
 
-impl<T> Div<T> for Vec4<T> where T: Div<Output=T>, T: Clone {
-    type Output = Vec4<T>;
-    fn div(self, other: T) -> Self::Output {
-        Vec4::new(self.x / other.clone(), self.y / other.clone(), self.z / other.clone(), self.w / other.clone())
+impl<T, const N: usize> VecN<T, N> {
+    pub fn display(&self, style: Style) -> Styled<'_, T, N> {
+        Styled { vec: self, style }
     }
 }
 
-impl<T> Vec4<T> where T: Clone {
-    pub fn new(x: T, y: T, z: T, w: T) -> Self {
-        Vec4 { x, y, z, w }
+// same as Style::Tuple
+impl<T, const N: usize> fmt::Display for VecN<T, N> where T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display(Style::Tuple))
     }
+}
 
-    pub fn zero() -> Vec4<T> where T: Default {
-        Default::default()
+// Accepts the layouts of Style as well as whitespace separated components. Named components
+// may come in any order.
+impl<T, const N: usize> FromStr for VecN<T, N> where T: FromStr, T::Err: fmt::Display {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let inner = ["()", "[]", "<>", "{}"].iter()
+            .find(|brackets| trimmed.starts_with(&brackets[..1]) && trimmed.ends_with(&brackets[1..]) && trimmed.len() >= 2)
+            .map(|_| &trimmed[1..trimmed.len() - 1])
+            .unwrap_or(trimmed);
+        let parts: Vec<&str> = if inner.contains(',') {
+            inner.split(',').map(|p| p.trim()).collect()
+        } else {
+            inner.split_whitespace().collect()
+        };
+        if parts.len() != N {
+            return Err(format!("expected {} components in \"{}\", found {}", N, s, parts.len()));
+        }
+
+        let mut values: Vec<Option<T>> = (0..N).map(|_| None).collect();
+        let named = parts.iter().filter(|p| p.contains('=')).count();
+        if named != 0 && named != N {
+            return Err(format!("either all or no components must be named in \"{}\"", s));
+        }
+        for (i, part) in parts.iter().enumerate() {
+            let (index, value) = match part.find('=') {
+                Some(eq) => {
+                    let name = part[..eq].trim();
+                    let index = NAMES.iter().take(N).position(|n| name.len() == 1 && name.starts_with(*n))
+                        .ok_or(format!("unknown component {} in \"{}\"", name, s))?;
+                    (index, part[eq + 1..].trim())
+                }
+                None => (i, *part),
+            };
+            if values[index].is_some() {
+                return Err(format!("component {} given twice in \"{}\"", NAMES[index], s));
+            }
+            values[index] = Some(value.parse::<T>().map_err(|e| format!("{} (\"{}\" in \"{}\")", e, value, s))?);
+        }
+        let components: Vec<T> = values.into_iter().map(|v| v.unwrap()).collect();
+        match components.try_into() {
+            Ok(components) => Ok(VecN { components }),
+            Err(_) => Err(format!("expected {} components in \"{}\"", N, s)),
+        }
     }
+}
 
-    pub fn mag(&self) -> T where T: Float {
-        self.sqr_mag().sqrt()
+// Named components ----------------------------------------------
+// VecN derefs to these for N <= 4 so that v.x, v.y, ... keep working. They have the same
+// layout as [T; N], which makes the pointer casts below sound.
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct X<T> {
+    pub x: T,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct XY<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[repr(C)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct XYZ<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[repr(C)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct XYZW<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
+}
+
+macro_rules! named_components {
+    ($n:literal, $target:ident, $($field:ident),+) => {
+        impl<T> Deref for VecN<T, $n> {
+            type Target = $target<T>;
+            fn deref(&self) -> &$target<T> {
+                unsafe { &*(self as *const VecN<T, $n> as *const $target<T>) }
+            }
+        }
+
+        impl<T> DerefMut for VecN<T, $n> {
+            fn deref_mut(&mut self) -> &mut $target<T> {
+                unsafe { &mut *(self as *mut VecN<T, $n> as *mut $target<T>) }
+            }
+        }
+
+        impl<T> VecN<T, $n> {
+            pub const fn new($($field: T),+) -> Self {
+                VecN { components: [$($field),+] }
+            }
+        }
+    };
+}
+
+named_components!(1, X, x);
+named_components!(2, XY, x, y);
+named_components!(3, XYZ, x, y, z);
+named_components!(4, XYZW, x, y, z, w);
+
+impl<T> From<(T, T)> for VecN<T, 2> {
+    fn from((x, y): (T, T)) -> Self {
+        VecN { components: [x, y] }
     }
+}
 
-    pub fn sqr_mag(&self) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * self.x.clone() + self.y.clone() * self.y.clone() + self.z.clone() * self.z.clone() + self.w.clone() * self.w.clone()
+impl<T> From<VecN<T, 2>> for (T, T) where T: Copy {
+    fn from(v: VecN<T, 2>) -> Self {
+        (v.components[0], v.components[1])
     }
+}
 
-    pub fn dist(&self, other: &Vec4<T>) -> T where T: Float {
-        (self.clone() - other.clone()).mag()
+impl<T> From<(T, T, T)> for VecN<T, 3> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        VecN { components: [x, y, z] }
     }
+}
 
-    pub fn dist_manhattan(&self, other: &Vec4<T>) -> T where T: Signed {
-        (self.x.clone() - other.x.clone()).abs() + (self.y.clone() - other.y.clone()).abs() + (self.z.clone() - other.z.clone()).abs() + (self.w.clone() - other.w.clone()).abs()
+impl<T> From<VecN<T, 3>> for (T, T, T) where T: Copy {
+    fn from(v: VecN<T, 3>) -> Self {
+        (v.components[0], v.components[1], v.components[2])
     }
+}
 
-    pub fn dot(&self, other: &Vec4<T>) -> T where T: Mul<Output=T>, T: Add<Output=T> {
-        self.x.clone() * other.x.clone() + self.y.clone() * other.y.clone() + self.z.clone() * other.z.clone() + self.w.clone() * other.w.clone()
+impl<T> From<(T, T, T, T)> for VecN<T, 4> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Self {
+        VecN { components: [x, y, z, w] }
     }
 }
 
-impl<T> fmt::Display for Vec4<T> where T: fmt::Debug, T: Clone {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+impl<T> From<VecN<T, 4>> for (T, T, T, T) where T: Copy {
+    fn from(v: VecN<T, 4>) -> Self {
+        (v.components[0], v.components[1], v.components[2], v.components[3])
     }
 }